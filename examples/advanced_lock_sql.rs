@@ -478,30 +478,17 @@ fn main() {
     println!("--- Active users with completed orders > $100 ---");
     let start = Instant::now();
     
-    let user_locks: Vec<_> = users
-        .lock_query()
-        .where_(User::status(), |s| s == "active")
-        .limit(100)  // Pre-filter users
-        .iter()
-        .map(|u| Arc::new(RwLock::new(u.clone())))
-        .collect::<Vec<_>>();
-    
-    let order_locks: Vec<_> = orders
-        .lock_query()
-        .where_(Order::status(), |s| s == "completed")
-        .where_(Order::total(), |&t| t > 100.0)
-        .limit(100)  // Pre-filter orders
-        .iter()
-        .map(|o| Arc::new(RwLock::new(o.clone())))
-        .collect::<Vec<_>>();
-    
-    let user_lock_refs: Vec<_> = user_locks.iter().map(|arc| &**arc).collect();
-    let order_lock_refs: Vec<_> = order_locks.iter().map(|arc| &**arc).collect();
+    let user_locks: Vec<_> = users.values().collect();
+    let order_locks: Vec<_> = orders.values().collect();
     
-    let filtered_joins = LockJoinQuery::new(user_lock_refs, order_lock_refs)
-        .inner_join(
+    // The WHERE clause runs inside the join, so rejected pairs are never built
+    // and nothing is cloned into new locks. Unlike pre-filtering with
+    // `.limit(100)`, every matching pair is returned.
+    let filtered_joins = LockJoinQuery::new(user_locks, order_locks)
+        .inner_join_where(
             User::id(),
             Order::user_id(),
+            |user, order| user.status == "active" && order.status == "completed" && order.total > 100.0,
             |user, order| {
                 format!("{} - Order #{} - ${:.2}", user.name, order.id, order.total)
             }
@@ -542,10 +529,10 @@ fn main() {
     println!("  Subquery result: {} user IDs", users_with_orders_view.count());
     
     // Step 2: Filter users by those IDs
-    let user_ids_with_orders = users_with_orders_view.get();
+    let user_ids_with_orders = users_with_orders_view.get().iter().copied();
     let active_buyers: Vec<_> = users
         .lock_query()
-        .where_(User::id(), |id| user_ids_with_orders.contains(id))
+        .where_in_subquery(User::id(), user_ids_with_orders)
         .all();
     
     println!("  Final result: {} users", active_buyers.len());
@@ -13,9 +13,9 @@
 //! cargo run --example lock_extensions_demo --features tokio
 
 #[cfg(feature = "parking_lot")]
-use rust_queries_builder::lock_ext::{
-    ParkingLotRwLockWrapper, ParkingLotQueryExt, ParkingLotJoinExt
-};
+use rust_queries_builder::lock_ext::{ParkingLotRwLockWrapper, ParkingLotJoinExt};
+#[cfg(feature = "parking_lot")]
+use rust_queries_builder::{LockQueryable, LockLazyQueryable};
 
 use key_paths_derive::Keypath;
 use std::collections::HashMap;
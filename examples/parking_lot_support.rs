@@ -102,51 +102,13 @@ impl<T> LockValue<T> for ParkingLotMutexWrapper<T> {
 }
 
 // ============================================================================
-// Step 3: Extension Trait for Direct Method Calls
+// Step 3: Direct Method Calls
 // ============================================================================
 
-use rust_queries_builder::{LockQuery, LockLazyQuery};
-
-/// Extension trait to enable direct .lock_query() and .lock_lazy_query() calls
-/// on HashMap with parking_lot locks.
-pub trait ParkingLotQueryExt<V> {
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotRwLockWrapper<V>>;
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotRwLockWrapper<V>, impl Iterator<Item = &ParkingLotRwLockWrapper<V>>>;
-}
-
-impl<K, V: 'static> ParkingLotQueryExt<V> for HashMap<K, ParkingLotRwLockWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotRwLockWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotRwLockWrapper<V>, impl Iterator<Item = &ParkingLotRwLockWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-/// Extension trait for Mutex queries.
-pub trait ParkingLotMutexQueryExt<V> {
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotMutexWrapper<V>>;
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotMutexWrapper<V>, impl Iterator<Item = &ParkingLotMutexWrapper<V>>>;
-}
-
-impl<K, V: 'static> ParkingLotMutexQueryExt<V> for HashMap<K, ParkingLotMutexWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotMutexWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotMutexWrapper<V>, impl Iterator<Item = &ParkingLotMutexWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
+// LockQueryable and LockLazyQueryable are implemented for every HashMap and Vec
+// whose values implement LockValue, so the wrappers above get .lock_query()
+// and .lock_lazy_query() with no extra code.
+use rust_queries_builder::{LockQueryable, LockLazyQueryable};
 
 // ============================================================================
 // Type Aliases
@@ -798,7 +760,7 @@ fn main() {
     println!("\n🚀 Extension Pattern (3 Steps):");
     println!("  1. Create newtype wrapper (ParkingLotRwLockWrapper<T>)");
     println!("  2. Implement LockValue trait");
-    println!("  3. Create extension trait (ParkingLotQueryExt<V>)");
+    println!("  3. Call .lock_query() via the generic LockQueryable trait");
 
     println!("\n⚡ Performance:");
     println!("  • Lazy evaluation: 50-150x faster for limited queries");
@@ -11,8 +11,6 @@
 //
 // cargo run --example tokio_rwlock_support
 
-// Note: We can't directly implement LockQueryable/LockLazyQueryable for HashMap<K, TokioLock<V>>
-// due to Rust's orphan rules. Instead, we use helper functions.
 use key_paths_derive::Keypath;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -93,24 +91,13 @@ impl<T> LockValue<T> for TokioLock<T> {
 }
 
 // ============================================================================
-// Step 3: Helper functions for querying
+// Step 3: Direct method calls
 // ============================================================================
 
-use rust_queries_builder::{LockQuery, LockLazyQuery};
-
-/// Helper function to create a LockQuery from a HashMap of TokioLocks.
-fn lock_query<V: 'static>(map: &HashMap<impl std::hash::Hash + Eq, TokioLock<V>>) -> LockQuery<'_, V, TokioLock<V>> {
-    let locks: Vec<_> = map.values().collect();
-    LockQuery::from_locks(locks)
-}
-
-/// Helper function to create a LockLazyQuery from a HashMap of TokioLocks.
-fn lock_lazy_query<V: 'static, K>(map: &HashMap<K, TokioLock<V>>) -> LockLazyQuery<'_, V, TokioLock<V>, impl Iterator<Item = &TokioLock<V>>>
-where
-    K: std::hash::Hash + Eq,
-{
-    LockLazyQuery::new(map.values())
-}
+// LockQueryable and LockLazyQueryable are implemented for every HashMap and Vec
+// whose values implement LockValue, so TokioLock maps get .lock_query() and
+// .lock_lazy_query() with no extra code.
+use rust_queries_builder::{LockQueryable, LockLazyQueryable};
 
 // ============================================================================
 // Helper functions for creating test data
@@ -250,7 +237,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- Find active users ---");
-    let active_users = lock_query(&users)
+    let active_users = users.lock_query()
         .where_(User::status(), |s| s == "active")
         .all();
     
@@ -261,7 +248,7 @@ async fn main() {
     println!("  SQL: SELECT * FROM users WHERE status = 'active';\n");
 
     println!("--- Find products in Electronics category ---");
-    let electronics = lock_query(&products)
+    let electronics = products.lock_query()
         .where_(Product::category(), |c| c == "Electronics")
         .all();
     
@@ -279,7 +266,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- SELECT user names ---");
-    let names: Vec<String> = lock_query(&users)
+    let names: Vec<String> = users.lock_query()
         .select(User::name());
     
     println!("  Extracted: {} names", names.len());
@@ -296,7 +283,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- Users ordered by score (descending) ---");
-    let ordered = lock_query(&users)
+    let ordered = users.lock_query()
         .order_by_float_desc(User::score());
     
     println!("  Top users by score:");
@@ -313,21 +300,21 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- COUNT active users ---");
-    let count = lock_query(&users)
+    let count = users.lock_query()
         .where_(User::status(), |s| s == "active")
         .count();
     println!("  Active users: {}", count);
     println!("  SQL: SELECT COUNT(*) FROM users WHERE status = 'active';\n");
 
     println!("--- AVG user score ---");
-    let avg_score = lock_query(&users)
+    let avg_score = users.lock_query()
         .avg(User::score())
         .unwrap_or(0.0);
     println!("  Average score: {:.2}", avg_score);
     println!("  SQL: SELECT AVG(score) FROM users;\n");
 
     println!("--- SUM of product stock ---");
-    let total_stock: u32 = lock_query(&products)
+    let total_stock: u32 = products.lock_query()
         .sum(Product::stock());
     println!("  Total stock: {} units", total_stock);
     println!("  SQL: SELECT SUM(stock) FROM products;\n");
@@ -340,7 +327,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- Products grouped by category ---");
-    let by_category = lock_query(&products)
+    let by_category = products.lock_query()
         .group_by(Product::category());
     
     println!("  Categories: {}", by_category.len());
@@ -359,7 +346,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- FIRST: Find first inactive user ---");
-    let first_inactive = lock_lazy_query(&users)
+    let first_inactive = users.lock_lazy_query()
         .where_(User::status(), |s| s == "inactive")
         .first();
     
@@ -370,7 +357,7 @@ async fn main() {
     println!("  SQL: SELECT * FROM users WHERE status = 'inactive' LIMIT 1;\n");
 
     println!("--- EXISTS: Check if any out-of-stock products ---");
-    let out_of_stock = lock_lazy_query(&products)
+    let out_of_stock = products.lock_lazy_query()
         .where_(Product::stock(), |&s| s == 0)
         .any();
     
@@ -378,7 +365,7 @@ async fn main() {
     println!("  SQL: SELECT EXISTS(SELECT 1 FROM products WHERE stock = 0);\n");
 
     println!("--- TAKE: First 2 active users ---");
-    let first_two: Vec<_> = lock_lazy_query(&users)
+    let first_two: Vec<_> = users.lock_lazy_query()
         .where_(User::status(), |s| s == "active")
         .take_lazy(2)
         .collect();
@@ -397,7 +384,7 @@ async fn main() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     println!("--- Active users with high scores (>= 90) ---");
-    let high_scorers = lock_query(&users)
+    let high_scorers = users.lock_query()
         .where_(User::status(), |s| s == "active")
         .where_(User::score(), |&score| score >= 90.0)
         .order_by_float_desc(User::score());
@@ -409,7 +396,7 @@ async fn main() {
     println!("  SQL: SELECT * FROM users WHERE status = 'active' AND score >= 90 ORDER BY score DESC;\n");
 
     println!("--- Affordable in-stock electronics ---");
-    let affordable = lock_query(&products)
+    let affordable = products.lock_query()
         .where_(Product::category(), |c| c == "Electronics")
         .where_(Product::price(), |&p| p < 100.0)
         .where_(Product::stock(), |&s| s > 0)
@@ -438,7 +425,7 @@ async fn main() {
     
     // Eager
     let start = Instant::now();
-    let eager_all = lock_query(&large_users)
+    let eager_all = large_users.lock_query()
         .where_(User::status(), |s| s == "inactive")
         .all();
     let _eager_first = eager_all.first().cloned();
@@ -446,7 +433,7 @@ async fn main() {
     
     // Lazy
     let start = Instant::now();
-    let _lazy_first = lock_lazy_query(&large_users)
+    let _lazy_first = large_users.lock_lazy_query()
         .where_(User::status(), |s| s == "inactive")
         .first();
     let lazy_time = start.elapsed();
@@ -463,7 +450,7 @@ async fn main() {
     
     // Eager
     let start = Instant::now();
-    let eager_all = lock_query(&large_products)
+    let eager_all = large_products.lock_query()
         .where_(Product::price(), |&p| p > 900.0)
         .all();
     let _eager_exists = !eager_all.is_empty();
@@ -471,7 +458,7 @@ async fn main() {
     
     // Lazy
     let start = Instant::now();
-    let _lazy_exists = lock_lazy_query(&large_products)
+    let _lazy_exists = large_products.lock_lazy_query()
         .where_(Product::price(), |&p| p > 900.0)
         .any();
     let lazy_time = start.elapsed();
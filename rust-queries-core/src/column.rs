@@ -0,0 +1,57 @@
+//! Support code for the `get_column` method generated by `#[derive(QueryBuilder)]`
+//! and the column enum generated by `#[derive(OrderColumn)]`.
+//!
+//! Not part of the public API. Field values are rendered with `Display` when
+//! the field type implements it and with `Debug` otherwise, and compared with
+//! `Ord` when available and `PartialOrd` otherwise; the choice is made at
+//! compile time through method resolution on [`Column`].
+
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+
+/// Borrowed field value waiting to be rendered.
+pub struct Column<'a, T: ?Sized>(pub &'a T);
+
+/// Renders a column with `Display`; preferred when available.
+pub trait ViaDisplay {
+    fn render_column(&self) -> String;
+}
+
+impl<T: Display + ?Sized> ViaDisplay for Column<'_, T> {
+    fn render_column(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Renders a column with `Debug`; used for types without `Display`.
+pub trait ViaDebug {
+    fn render_column(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> ViaDebug for &Column<'_, T> {
+    fn render_column(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Compares a column with `Ord`; preferred when available.
+pub trait ViaOrd<T: ?Sized> {
+    fn compare_column(&self, other: &T) -> Ordering;
+}
+
+impl<T: Ord + ?Sized> ViaOrd<T> for Column<'_, T> {
+    fn compare_column(&self, other: &T) -> Ordering {
+        self.0.cmp(other)
+    }
+}
+
+/// Compares a column with `PartialOrd`; incomparable values (such as NaN) are equal.
+pub trait ViaPartialOrd<T: ?Sized> {
+    fn compare_column(&self, other: &T) -> Ordering;
+}
+
+impl<T: PartialOrd + ?Sized> ViaPartialOrd<T> for &Column<'_, T> {
+    fn compare_column(&self, other: &T) -> Ordering {
+        self.0.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+}
@@ -1,4 +1,6 @@
 use crate::{Query, LazyQuery, Queryable};
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
 
 /// Extension trait that adds eager Query methods directly to slice-like containers
 /// 
@@ -78,6 +80,36 @@ where
     }
 }
 
+/// Extension trait that starts a lazy query from a key range of a `BTreeMap`
+/// 
+/// The range is resolved by the map itself, so only values whose keys fall in
+/// the range are ever visited by later `where_` filters.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// use rust_queries_builder::RangeQueryExt;
+/// 
+/// let orders: BTreeMap<u64, Order> = ...;
+/// let results: Vec<_> = orders.query_range(1_000..2_000)
+///     .where_(Order::status(), |s| s == "shipped")
+///     .collect();
+/// ```
+pub trait RangeQueryExt<K, T> {
+    /// Create a lazy query over the values whose keys fall in `range`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `range` - The key range to scan, e.g. `10..20` or `..=5`
+    fn query_range<R: RangeBounds<K>>(&self, range: R) -> LazyQuery<'_, T, Box<dyn Iterator<Item = &T> + '_>>;
+}
+
+impl<K: Ord, T: 'static> RangeQueryExt<K, T> for BTreeMap<K, T> {
+    fn query_range<R: RangeBounds<K>>(&self, range: R) -> LazyQuery<'_, T, Box<dyn Iterator<Item = &T> + '_>> {
+        LazyQuery::from_iter(Box::new(self.range(range).map(|(_, v)| v)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -401,4 +433,36 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name, "Mouse");
     }
+
+    #[test]
+    fn test_btreemap_query_range() {
+        let mut map: BTreeMap<u32, Product> = BTreeMap::new();
+        for id in 1..=6 {
+            map.insert(
+                id,
+                Product {
+                    id,
+                    name: format!("Item {}", id),
+                    price: id as f64 * 10.0,
+                    category: if id % 2 == 0 { "Even" } else { "Odd" }.to_string(),
+                },
+            );
+        }
+
+        // Keys 2..5 are 2, 3, 4; only those values are filtered
+        let ids: Vec<u32> = map
+            .query_range(2..5)
+            .where_(Product::price(), |&p| p >= 0.0)
+            .map_items(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+
+        let evens = map
+            .query_range(..=4)
+            .where_(Product::category(), |cat| cat == "Even")
+            .count();
+        assert_eq!(evens, 2);
+
+        assert_eq!(map.query_range(10..).count(), 0);
+    }
 }
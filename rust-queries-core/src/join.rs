@@ -6,6 +6,26 @@
 use key_paths_core::KeyPaths;
 use std::collections::HashMap;
 
+/// Errors returned by fallible join operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    /// The join would produce more rows than the allowed cap.
+    TooLarge {
+        /// The number of rows the join would have produced (saturating at `usize::MAX`).
+        would_be: usize,
+    },
+}
+
+impl std::fmt::Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinError::TooLarge { would_be } => write!(f, "join would produce {} rows, above the cap", would_be),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
 /// A query builder for joining two collections.
 ///
 /// Supports inner joins, left joins, and filtered joins using key-paths for type-safe
@@ -104,6 +124,130 @@ impl<'a, L: 'static, R: 'static> JoinQuery<'a, L, R> {
         results
     }
 
+    /// Performs an inner join whose mapper may borrow from the joined items.
+    ///
+    /// Like `inner_join`, but the `&L` and `&R` passed to `mapper` live as long
+    /// as the joined collections, so the result can hold references such as
+    /// `&user.name` instead of clones.
+    ///
+    /// # Arguments
+    ///
+    /// * `left_key` - Key-path to the join field in the left collection
+    /// * `right_key` - Key-path to the join field in the right collection
+    /// * `mapper` - Function to transform matching pairs into the result type
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let names_and_totals: Vec<(&str, f64)> = JoinQuery::new(&users, &orders)
+    ///     .inner_join_ref(User::id(), Order::user_id(), |user, order| {
+    ///         (user.name.as_str(), order.total)
+    ///     });
+    /// ```
+    pub fn inner_join_ref<K, O, F>(&self, left_key: KeyPaths<L, K>, right_key: KeyPaths<R, K>, mapper: F) -> Vec<O>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: Fn(&'a L, &'a R) -> O,
+    {
+        let index = JoinIndex::build(self.right, right_key);
+
+        let mut results = Vec::new();
+        for left_item in self.left.iter() {
+            if let Some(key) = left_key.get(left_item) {
+                for &right_item in index.probe(key) {
+                    results.push(mapper(left_item, right_item));
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Performs an inner join and returns the matching pairs as references.
+    ///
+    /// Nothing is cloned; the caller decides what, if anything, to copy out.
+    ///
+    /// # Arguments
+    ///
+    /// * `left_key` - Key-path to the join field in the left collection
+    /// * `right_key` - Key-path to the join field in the right collection
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (user, order) in JoinQuery::new(&users, &orders).inner_join_refs(User::id(), Order::user_id()) {
+    ///     println!("{} spent {}", user.name, order.total);
+    /// }
+    /// ```
+    pub fn inner_join_refs<K>(&self, left_key: KeyPaths<L, K>, right_key: KeyPaths<R, K>) -> Vec<(&'a L, &'a R)>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        self.inner_join_ref(left_key, right_key, |left, right| (left, right))
+    }
+
+    /// Performs an inner join and indexes the joined rows by a key of the result.
+    ///
+    /// Equivalent to `inner_join` followed by grouping the results, but builds
+    /// the index while joining instead of in a separate pass. Rows within each
+    /// group keep their join order.
+    ///
+    /// # Arguments
+    ///
+    /// * `left_key` - Key-path to the join field in the left collection
+    /// * `right_key` - Key-path to the join field in the right collection
+    /// * `key_of_result` - Function extracting the index key from a joined row
+    /// * `mapper` - Function to transform matching pairs into the result type
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let orders_by_user = JoinQuery::new(&users, &orders)
+    ///     .inner_join_index(
+    ///         User::id(),
+    ///         Order::user_id(),
+    ///         |row: &(u32, f64)| row.0,
+    ///         |user, order| (user.id, order.total),
+    ///     );
+    /// let user_1_orders = &orders_by_user[&1];
+    /// ```
+    pub fn inner_join_index<K, IK, O, F, G>(
+        &self,
+        left_key: KeyPaths<L, K>,
+        right_key: KeyPaths<R, K>,
+        key_of_result: G,
+        mapper: F,
+    ) -> HashMap<IK, Vec<O>>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        IK: Eq + std::hash::Hash,
+        F: Fn(&L, &R) -> O,
+        G: Fn(&O) -> IK,
+    {
+        // Build index for right side
+        let mut right_index: HashMap<K, Vec<&R>> = HashMap::new();
+        for item in self.right.iter() {
+            if let Some(key) = right_key.get(item).cloned() {
+                right_index.entry(key).or_default().push(item);
+            }
+        }
+
+        // Join left with indexed right, indexing each joined row as it is produced
+        let mut results: HashMap<IK, Vec<O>> = HashMap::new();
+        for left_item in self.left.iter() {
+            if let Some(key) = left_key.get(left_item).cloned() {
+                if let Some(right_items) = right_index.get(&key) {
+                    for right_item in right_items {
+                        let row = mapper(left_item, right_item);
+                        results.entry(key_of_result(&row)).or_default().push(row);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
     /// Performs a left join between two collections.
     ///
     /// Returns all items from the left collection with optional matching items
@@ -163,7 +307,9 @@ impl<'a, L: 'static, R: 'static> JoinQuery<'a, L, R> {
     /// Performs an inner join with an additional filter predicate.
     ///
     /// Like `inner_join`, but only includes pairs that satisfy both the join
-    /// condition and the additional predicate.
+    /// condition and the additional predicate. The predicate runs on each
+    /// key-matched pair before `mapper`, so rejected pairs are never built;
+    /// there is no need to pre-filter either side into a new collection.
     ///
     /// # Arguments
     ///
@@ -196,23 +342,14 @@ impl<'a, L: 'static, R: 'static> JoinQuery<'a, L, R> {
         F: Fn(&L, &R) -> O,
         P: Fn(&L, &R) -> bool,
     {
-        // Build index for right side
-        let mut right_index: HashMap<K, Vec<&R>> = HashMap::new();
-        for item in self.right.iter() {
-            if let Some(key) = right_key.get(item).cloned() {
-                right_index.entry(key).or_insert_with(Vec::new).push(item);
-            }
-        }
+        let index = JoinIndex::build(self.right, right_key);
 
-        // Join left with indexed right, applying predicate
         let mut results = Vec::new();
         for left_item in self.left.iter() {
-            if let Some(key) = left_key.get(left_item).cloned() {
-                if let Some(right_items) = right_index.get(&key) {
-                    for right_item in right_items {
-                        if predicate(left_item, right_item) {
-                            results.push(mapper(left_item, right_item));
-                        }
+            if let Some(key) = left_key.get(left_item) {
+                for &right_item in index.probe(key) {
+                    if predicate(left_item, right_item) {
+                        results.push(mapper(left_item, right_item));
                     }
                 }
             }
@@ -308,5 +445,455 @@ impl<'a, L: 'static, R: 'static> JoinQuery<'a, L, R> {
         results
     }
 
+    /// Performs a cross join, or returns an error if the product would exceed `cap` rows.
+    ///
+    /// The size is checked before any row is built, so an accidental
+    /// billion-row product fails fast instead of exhausting memory. A product
+    /// of exactly `cap` rows is allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `cap` - The maximum number of rows to produce
+    /// * `mapper` - Function to transform pairs into the result type
+    ///
+    /// # Errors
+    ///
+    /// Returns `JoinError::TooLarge` if `left.len() * right.len()` exceeds `cap`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// match JoinQuery::new(&colors, &sizes).cross_join_capped(10_000, |c, s| (c.id, s.id)) {
+    ///     Ok(variants) => render(variants),
+    ///     Err(JoinError::TooLarge { would_be }) => warn!("refusing {} variants", would_be),
+    /// }
+    /// ```
+    pub fn cross_join_capped<O, F>(&self, cap: usize, mapper: F) -> Result<Vec<O>, JoinError>
+    where
+        F: Fn(&L, &R) -> O,
+    {
+        let would_be = self.left.len().saturating_mul(self.right.len());
+        if would_be > cap {
+            return Err(JoinError::TooLarge { would_be });
+        }
+        Ok(self.cross_join(mapper))
+    }
+
+    /// Performs an inner join against a prebuilt right-side index.
+    ///
+    /// Use this instead of `inner_join` when the same right collection is
+    /// joined against several left collections, so the hash index is built
+    /// only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The left collection to join
+    /// * `index` - Index of the right collection built with `JoinIndex::build`
+    /// * `left_key` - Key-path to the join field in the left collection
+    /// * `mapper` - Function to transform matching pairs into the result type
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let index = JoinIndex::build(&orders, Order::user_id());
+    /// let active = JoinQuery::inner_join_with_index(&active_users, &index, User::id(), |u, o| (u.id, o.total));
+    /// let vip = JoinQuery::inner_join_with_index(&vip_users, &index, User::id(), |u, o| (u.id, o.total));
+    /// ```
+    pub fn inner_join_with_index<K, O, F>(
+        left: &'a [L],
+        index: &JoinIndex<'a, K, R>,
+        left_key: KeyPaths<L, K>,
+        mapper: F,
+    ) -> Vec<O>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: Fn(&L, &R) -> O,
+    {
+        let mut results = Vec::new();
+        for left_item in left.iter() {
+            if let Some(key) = left_key.get(left_item) {
+                for right_item in index.probe(key) {
+                    results.push(mapper(left_item, right_item));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+/// A reusable hash index over the right side of a join.
+///
+/// Building the index is the O(m) part of a hash join; probing it is O(1) per
+/// key. Keeping the index separate lets one right collection be joined
+/// against many left collections without rebuilding it.
+///
+/// # Example
+///
+/// ```ignore
+/// let index = JoinIndex::build(&orders, Order::user_id());
+/// let alice_orders = index.probe(&1);
+/// ```
+pub struct JoinIndex<'a, K, R: 'static> {
+    index: HashMap<K, Vec<&'a R>>,
+}
+
+impl<'a, K, R: 'static> JoinIndex<'a, K, R>
+where
+    K: Eq + std::hash::Hash + Clone + 'static,
+{
+    /// Builds an index of `right` keyed by `right_key`.
+    ///
+    /// Items whose key-path yields `None` are left out of the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `right` - The collection to index
+    /// * `right_key` - Key-path to the join field in the collection
+    pub fn build(right: &'a [R], right_key: KeyPaths<R, K>) -> Self {
+        let mut index: HashMap<K, Vec<&'a R>> = HashMap::new();
+        for item in right.iter() {
+            if let Some(key) = right_key.get(item).cloned() {
+                index.entry(key).or_default().push(item);
+            }
+        }
+        Self { index }
+    }
+
+    /// Returns the indexed items with the given key, in their original order.
+    ///
+    /// Returns an empty slice if no item has the key.
+    pub fn probe(&self, key: &K) -> &[&'a R] {
+        self.index.get(key).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// A query builder for joining three collections in two steps.
+///
+/// The first two collections are joined on a shared key, then each matching
+/// pair is joined against the third collection on a key taken from the pair.
+/// This models the common three-table star join such as users, orders and
+/// products.
+///
+/// # Type Parameters
+///
+/// * `'a` - The lifetime of the data being joined
+/// * `A` - The type of items in the first collection
+/// * `B` - The type of items in the second collection
+/// * `C` - The type of items in the third collection
+///
+/// # Example
+///
+/// ```ignore
+/// let rows = JoinQuery3::new(&users, &orders, &products).inner_join(
+///     User::id(),
+///     Order::user_id(),
+///     |_, order| order.product_id,
+///     Product::id(),
+///     |user, order, product| (user.name.clone(), order.id, product.name.clone()),
+/// );
+/// ```
+pub struct JoinQuery3<'a, A: 'static, B: 'static, C: 'static> {
+    first: &'a [A],
+    second: &'a [B],
+    third: &'a [C],
+}
+
+impl<'a, A: 'static, B: 'static, C: 'static> JoinQuery3<'a, A, B, C> {
+    /// Creates a new three-way join query.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The first collection
+    /// * `second` - The collection joined to `first`
+    /// * `third` - The collection joined to each `(first, second)` pair
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let join = JoinQuery3::new(&users, &orders, &products);
+    /// ```
+    pub fn new(first: &'a [A], second: &'a [B], third: &'a [C]) -> Self {
+        Self { first, second, third }
+    }
+
+    /// Performs an inner join across all three collections.
+    ///
+    /// Only triples matching on both keys are returned. Both the second and
+    /// third collections are indexed by hash, so the cost is linear in the
+    /// input sizes plus the number of results.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_key` - Key-path to the join field in the first collection
+    /// * `second_key` - Key-path to the matching field in the second collection
+    /// * `pair_key` - Function extracting the key for the third collection from a joined pair
+    /// * `third_key` - Key-path to the join field in the third collection
+    /// * `mapper` - Function to transform matching triples into the result type
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let rows = JoinQuery3::new(&users, &orders, &products).inner_join(
+    ///     User::id(),
+    ///     Order::user_id(),
+    ///     |_, order| order.product_id,
+    ///     Product::id(),
+    ///     |user, order, product| (user.name.clone(), order.id, product.name.clone()),
+    /// );
+    /// ```
+    pub fn inner_join<K1, K2, O, P, F>(
+        &self,
+        first_key: KeyPaths<A, K1>,
+        second_key: KeyPaths<B, K1>,
+        pair_key: P,
+        third_key: KeyPaths<C, K2>,
+        mapper: F,
+    ) -> Vec<O>
+    where
+        K1: Eq + std::hash::Hash + Clone + 'static,
+        K2: Eq + std::hash::Hash + Clone + 'static,
+        P: Fn(&A, &B) -> K2,
+        F: Fn(&A, &B, &C) -> O,
+    {
+        let mut second_index: HashMap<K1, Vec<&B>> = HashMap::new();
+        for item in self.second.iter() {
+            if let Some(key) = second_key.get(item).cloned() {
+                second_index.entry(key).or_default().push(item);
+            }
+        }
+
+        let mut third_index: HashMap<K2, Vec<&C>> = HashMap::new();
+        for item in self.third.iter() {
+            if let Some(key) = third_key.get(item).cloned() {
+                third_index.entry(key).or_default().push(item);
+            }
+        }
+
+        let mut results = Vec::new();
+        for first_item in self.first.iter() {
+            let Some(key) = first_key.get(first_item) else {
+                continue;
+            };
+            for second_item in second_index.get(key).into_iter().flatten() {
+                let key = pair_key(first_item, second_item);
+                for third_item in third_index.get(&key).into_iter().flatten() {
+                    results.push(mapper(first_item, second_item, third_item));
+                }
+            }
+        }
+
+        results
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use key_paths_derive::Keypath;
+
+    #[derive(Keypath)]
+    struct User {
+        id: u32,
+        name: String,
+    }
+
+    #[derive(Keypath)]
+    struct Order {
+        id: u32,
+        user_id: u32,
+        product_id: u32,
+        total: f64,
+    }
+
+    #[derive(Keypath)]
+    struct Product {
+        id: u32,
+        name: String,
+    }
+
+    fn users() -> Vec<User> {
+        vec![
+            User { id: 1, name: "Alice".to_string() },
+            User { id: 2, name: "Bob".to_string() },
+            User { id: 3, name: "Carol".to_string() },
+        ]
+    }
+
+    fn orders() -> Vec<Order> {
+        vec![
+            Order { id: 10, user_id: 1, product_id: 100, total: 50.0 },
+            Order { id: 11, user_id: 2, product_id: 101, total: 20.0 },
+            Order { id: 12, user_id: 1, product_id: 101, total: 75.0 },
+            Order { id: 13, user_id: 9, product_id: 100, total: 5.0 },
+        ]
+    }
+
+    #[test]
+    fn test_inner_join_index_groups_rows_by_key() {
+        let users = users();
+        let orders = orders();
+
+        let index = JoinQuery::new(&users, &orders).inner_join_index(
+            User::id(),
+            Order::user_id(),
+            |row: &(u32, String, u32)| row.0,
+            |user, order| (user.id, user.name.clone(), order.id),
+        );
+
+        assert_eq!(index.len(), 2);
+        let alice: Vec<u32> = index[&1].iter().map(|row| row.2).collect();
+        assert_eq!(alice, vec![10, 12]);
+        assert!(index[&1].iter().all(|row| row.1 == "Alice"));
+        assert_eq!(index[&2].len(), 1);
+        assert!(!index.contains_key(&3));
+
+        let joined = JoinQuery::new(&users, &orders)
+            .inner_join(User::id(), Order::user_id(), |_, order| order.total);
+        let indexed_total: f64 = JoinQuery::new(&users, &orders)
+            .inner_join_index(
+                User::id(),
+                Order::user_id(),
+                |row: &(String, f64)| row.0.clone(),
+                |user, order| (user.name.clone(), order.total),
+            )
+            .values()
+            .flatten()
+            .map(|row| row.1)
+            .sum();
+        assert_eq!(joined.iter().sum::<f64>(), indexed_total);
+    }
+
+    #[test]
+    fn test_three_way_inner_join() {
+        let users = users();
+        let mut orders = orders();
+        // Carol's order points at a product that does not exist.
+        orders.push(Order { id: 14, user_id: 3, product_id: 999, total: 9.0 });
+        let products = vec![
+            Product { id: 100, name: "Laptop".to_string() },
+            Product { id: 101, name: "Mouse".to_string() },
+        ];
+
+        let rows = JoinQuery3::new(&users, &orders, &products).inner_join(
+            User::id(),
+            Order::user_id(),
+            |_, order| order.product_id,
+            Product::id(),
+            |user, order, product| (user.name.clone(), order.id, product.name.clone()),
+        );
+
+        assert_eq!(
+            rows,
+            vec![
+                ("Alice".to_string(), 10, "Laptop".to_string()),
+                ("Alice".to_string(), 12, "Mouse".to_string()),
+                ("Bob".to_string(), 11, "Mouse".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inner_join_with_reused_index() {
+        let orders = orders();
+        let index = JoinIndex::build(&orders, Order::user_id());
+        assert_eq!(index.probe(&1).len(), 2);
+        assert!(index.probe(&42).is_empty());
+
+        let first_batch = users();
+        let second_batch = vec![
+            User { id: 2, name: "Bob".to_string() },
+            User { id: 9, name: "Zed".to_string() },
+            User { id: 7, name: "Nobody".to_string() },
+        ];
+
+        let first = JoinQuery::inner_join_with_index(&first_batch, &index, User::id(), |user, order| {
+            (user.name.clone(), order.id)
+        });
+        assert_eq!(
+            first,
+            vec![
+                ("Alice".to_string(), 10),
+                ("Alice".to_string(), 12),
+                ("Bob".to_string(), 11),
+            ]
+        );
+
+        let second = JoinQuery::inner_join_with_index(&second_batch, &index, User::id(), |user, order| {
+            (user.name.clone(), order.id)
+        });
+        assert_eq!(second, vec![("Bob".to_string(), 11), ("Zed".to_string(), 13)]);
+    }
+
+    #[test]
+    fn test_inner_join_refs_borrow_without_cloning() {
+        let users = users();
+        let orders = orders();
+
+        let pairs = JoinQuery::new(&users, &orders).inner_join_refs(User::id(), Order::user_id());
+        let ids: Vec<(u32, u32)> = pairs.iter().map(|(u, o)| (u.id, o.id)).collect();
+        assert_eq!(ids, vec![(1, 10), (1, 12), (2, 11)]);
+        assert!(std::ptr::eq(pairs[0].0, &users[0]));
+
+        let rows: Vec<(&str, f64)> = JoinQuery::new(&users, &orders)
+            .inner_join_ref(User::id(), Order::user_id(), |user, order| (user.name.as_str(), order.total));
+        assert_eq!(rows, vec![("Alice", 50.0), ("Alice", 75.0), ("Bob", 20.0)]);
+    }
+
+    #[test]
+    fn test_cross_join_capped() {
+        let users = users();
+        let orders = orders();
+        let join = JoinQuery::new(&users, &orders);
+
+        let pairs = join.cross_join_capped(12, |u, o| (u.id, o.id)).unwrap();
+        assert_eq!(pairs.len(), 12);
+        assert_eq!(pairs[..2], [(1, 10), (1, 11)]);
+
+        assert_eq!(
+            join.cross_join_capped(11, |u, o| (u.id, o.id)),
+            Err(JoinError::TooLarge { would_be: 12 })
+        );
+        assert_eq!(
+            JoinError::TooLarge { would_be: 12 }.to_string(),
+            "join would produce 12 rows, above the cap"
+        );
+    }
+
+    #[test]
+    fn test_inner_join_where_skips_rejected_pairs() {
+        use std::cell::Cell;
+
+        let (users, orders) = (users(), orders());
+        let joined = JoinQuery::new(&users, &orders);
+
+        let mut expected: Vec<(String, u32)> = joined
+            .inner_join_refs(User::id(), Order::user_id())
+            .into_iter()
+            .filter(|(_, order)| order.total > 30.0)
+            .map(|(user, order)| (user.name.clone(), order.id))
+            .collect();
+
+        let (checked, built) = (Cell::new(0), Cell::new(0));
+        let mut fused = joined.inner_join_where(
+            User::id(),
+            Order::user_id(),
+            |_, order| {
+                checked.set(checked.get() + 1);
+                order.total > 30.0
+            },
+            |user, order| {
+                built.set(built.get() + 1);
+                (user.name.clone(), order.id)
+            },
+        );
+
+        expected.sort_by_key(|(_, id)| *id);
+        fused.sort_by_key(|(_, id)| *id);
+        assert_eq!(fused, expected);
+        assert_eq!(fused, vec![("Alice".to_string(), 10), ("Alice".to_string(), 12)]);
+        // Three key matches were checked; Bob's order was rejected before mapping.
+        assert_eq!(checked.get(), 3);
+        assert_eq!(built.get(), 2);
+    }
+}
@@ -4,8 +4,10 @@
 //! until results are actually consumed.
 
 use key_paths_core::KeyPaths;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
 use std::marker::PhantomData;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 #[cfg(feature = "datetime")]
 use chrono::{DateTime, TimeZone};
@@ -105,6 +107,331 @@ where
         }
     }
 
+    /// Adds a filter predicate over the whole item (lazy).
+    ///
+    /// Useful for conditions spanning several fields, which a single key-path
+    /// predicate can't express.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let profitable: Vec<_> = LazyQuery::new(&products)
+    ///     .where_item(|p| p.price > p.cost)
+    ///     .collect();
+    /// ```
+    pub fn where_item<P>(self, predicate: P) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        P: Fn(&T) -> bool + 'a,
+    {
+        LazyQuery {
+            iter: self.iter.filter(move |item| predicate(item)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Adds a filter that carries mutable state from item to item (lazy).
+    ///
+    /// `f` receives the state and each item in iteration order and returns
+    /// whether to keep the item. Because the state depends on every item seen
+    /// so far, the result depends on the order of evaluation, which is only
+    /// well defined for sequential lazy queries. Earlier filters run first, so
+    /// the state only sees items that passed them.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The initial state
+    /// * `f` - Updates the state and decides whether to keep the item
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Keep items until the running total would exceed the budget
+    /// let affordable: Vec<_> = LazyQuery::new(&cart)
+    ///     .where_stateful(0.0, |spent, item| {
+    ///         *spent += item.price;
+    ///         *spent <= 100.0
+    ///     })
+    ///     .collect();
+    /// ```
+    pub fn where_stateful<St, P>(self, init: St, mut f: P) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        St: 'a,
+        P: FnMut(&mut St, &T) -> bool + 'a,
+    {
+        let mut state = init;
+        LazyQuery {
+            iter: self.iter.filter(move |item| f(&mut state, item)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Filters by a field being greater than a value (`>`, lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_gt(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_gt<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_(path, move |field| *field > value)
+    }
+
+    /// Filters by a field being greater than or equal to a value (`>=`, lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_ge(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_ge<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_(path, move |field| *field >= value)
+    }
+
+    /// Filters by a field being less than a value (`<`, lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_lt(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_lt<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_(path, move |field| *field < value)
+    }
+
+    /// Filters by a field being less than or equal to a value (`<=`, lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_le(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_le<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_(path, move |field| *field <= value)
+    }
+
+    /// Filters by a field being equal to a value (`=`, lazy).
+    ///
+    /// Compares exactly; see [`Query::where_eq`](crate::query::Query::where_eq) for floats.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_eq(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_eq<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialEq + 'static,
+    {
+        self.where_(path, move |field| *field == value)
+    }
+
+    /// Filters by a field being not equal to a value (`<>`, lazy).
+    ///
+    /// Compares exactly; see [`Query::where_eq`](crate::query::Query::where_eq) for floats.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<_> = LazyQuery::new(&products)
+    ///     .where_ne(Product::stock(), 10)
+    ///     .collect();
+    /// ```
+    pub fn where_ne<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialEq + 'static,
+    {
+        self.where_(path, move |field| *field != value)
+    }
+
+    /// Filters by comparing two fields of the same item (lazy).
+    ///
+    /// Keeps items where `a` compares to `b` with the given ordering. Items
+    /// where either key-path yields `None`, or the values are incomparable
+    /// (e.g. `NaN`), are excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key-path to the left-hand field
+    /// * `b` - The key-path to the right-hand field
+    /// * `ord` - The required ordering of `a` relative to `b`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let loss_makers: Vec<_> = LazyQuery::new(&products)
+    ///     .where_fields_cmp(Product::price(), Product::cost(), Ordering::Less)
+    ///     .collect();
+    /// ```
+    pub fn where_fields_cmp<F>(
+        self,
+        a: KeyPaths<T, F>,
+        b: KeyPaths<T, F>,
+        ord: std::cmp::Ordering,
+    ) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_item(move |item| match (a.get(item), b.get(item)) {
+            (Some(a), Some(b)) => a.partial_cmp(b) == Some(ord),
+            _ => false,
+        })
+    }
+
+    /// Filters by two fields of the same item being equal (lazy).
+    ///
+    /// Items where either key-path yields `None` are excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key-path to the first field
+    /// * `b` - The key-path to the second field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let at_cost: Vec<_> = LazyQuery::new(&products)
+    ///     .where_fields_eq(Product::price(), Product::cost())
+    ///     .collect();
+    /// ```
+    pub fn where_fields_eq<F>(self, a: KeyPaths<T, F>, b: KeyPaths<T, F>) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: PartialEq + 'static,
+    {
+        self.where_item(move |item| match (a.get(item), b.get(item)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        })
+    }
+
+    /// Filters by a `Copy` field, such as a fieldless enum, being equal to a value (lazy).
+    ///
+    /// Same as `where_eq`, named to read naturally for enum columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to match
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let active: Vec<_> = LazyQuery::new(&accounts)
+    ///     .where_is(Account::status(), Status::Active)
+    ///     .collect();
+    /// ```
+    pub fn where_is<F>(self, path: KeyPaths<T, F>, value: F) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: Copy + PartialEq + 'static,
+    {
+        self.where_eq(path, value)
+    }
+
+    /// Filters by a `Copy` field, such as a fieldless enum, being one of several values (`IN`, lazy).
+    ///
+    /// An empty `values` slice matches nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to compare
+    /// * `values` - The accepted values
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let open: Vec<_> = LazyQuery::new(&tickets)
+    ///     .where_is_any(Ticket::status(), &[Status::New, Status::InProgress])
+    ///     .collect();
+    /// ```
+    pub fn where_is_any<F>(self, path: KeyPaths<T, F>, values: &[F]) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        F: Copy + PartialEq + 'static,
+    {
+        let values = values.to_vec();
+        self.where_(path, move |field| values.contains(field))
+    }
+
+    /// Filters to rows valid at a point in time: `from <= at <= to` (lazy).
+    ///
+    /// Both bounds are inclusive, so an open-ended row can use `i64::MAX` as
+    /// its `to` value. Rows where either key-path yields `None` are excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key-path to the start of the validity window
+    /// * `to` - The key-path to the end of the validity window
+    /// * `at` - The point in time to query
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let current: Vec<_> = LazyQuery::new(&prices)
+    ///     .where_valid_at(Price::valid_from(), Price::valid_to(), now)
+    ///     .collect();
+    /// ```
+    pub fn where_valid_at(
+        self,
+        from: KeyPaths<T, i64>,
+        to: KeyPaths<T, i64>,
+        at: i64,
+    ) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a> {
+        self.where_item(move |item| match (from.get(item), to.get(item)) {
+            (Some(&from), Some(&to)) => from <= at && at <= to,
+            _ => false,
+        })
+    }
+
     /// Maps each item through a transformation (lazy).
     ///
     /// # Example
@@ -122,6 +449,32 @@ where
         self.iter.map(f)
     }
 
+    /// Pairs each item with every element of a small slice (lazy cross product).
+    ///
+    /// Yields `f(item, other)` for each matching item and each element of `others`,
+    /// in item-major order. Pairs are produced on demand, so `.take(k)` only
+    /// evaluates the first `k` combinations.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let offers: Vec<_> = LazyQuery::new(&users)
+    ///     .where_(User::active(), |&a| a)
+    ///     .product_with(&tiers, |user, tier| (user.id, tier.name.clone()))
+    ///     .take(100)
+    ///     .collect();
+    /// ```
+    pub fn product_with<U, R2, F>(self, others: &'a [U], f: F) -> impl Iterator<Item = R2> + 'a
+    where
+        U: 'a,
+        F: Fn(&'a T, &'a U) -> R2 + 'a,
+        I: 'a,
+    {
+        self.iter
+            .flat_map(move |item| others.iter().map(move |other| (item, other)))
+            .map(move |(item, other)| f(item, other))
+    }
+
     /// Selects/projects a field value (lazy).
     ///
     /// Returns iterator over cloned field values.
@@ -180,6 +533,170 @@ where
         }
     }
 
+    /// Takes items in order while their running total stays within a budget (terminal operation).
+    ///
+    /// Accumulates `value` over the items and stops before the first item that
+    /// would push the total above `budget`, so the returned items never exceed
+    /// it; an item that lands exactly on the budget is kept. Items without a
+    /// value count as `0`. Later, smaller items are not considered once the
+    /// budget would be exceeded. Use `take_until_sum_inclusive` to also keep
+    /// the item that crosses the budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The key-path to the f64 field to accumulate
+    /// * `budget` - The maximum running total
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Orders that fit in a $1000 shipment
+    /// let batch = LazyQuery::new(&orders).take_until_sum(Order::total(), 1000.0);
+    /// ```
+    pub fn take_until_sum(self, value: KeyPaths<T, f64>, budget: f64) -> Vec<&'a T> {
+        self.take_by_budget(value, budget, false)
+    }
+
+    /// Takes items in order until their running total exceeds a budget, keeping
+    /// the item that crosses it (terminal operation).
+    ///
+    /// Like `take_until_sum`, but the first item that pushes the total above
+    /// `budget` is included, so the result covers at least the budget whenever
+    /// there are enough items.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The key-path to the f64 field to accumulate
+    /// * `budget` - The running total to reach
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Enough orders to cover a $1000 minimum
+    /// let batch = LazyQuery::new(&orders).take_until_sum_inclusive(Order::total(), 1000.0);
+    /// ```
+    pub fn take_until_sum_inclusive(self, value: KeyPaths<T, f64>, budget: f64) -> Vec<&'a T> {
+        self.take_by_budget(value, budget, true)
+    }
+
+    fn take_by_budget(self, value: KeyPaths<T, f64>, budget: f64, keep_crossing: bool) -> Vec<&'a T> {
+        let mut total = 0.0;
+        let mut taken = Vec::new();
+        for item in self.iter {
+            total += value.get(item).copied().unwrap_or(0.0);
+            if total > budget {
+                if keep_crossing {
+                    taken.push(item);
+                }
+                break;
+            }
+            taken.push(item);
+        }
+        taken
+    }
+
+    /// Concatenates another lazy query after this one (lazy).
+    ///
+    /// Like `Iterator::chain`: yields this query's items, then `other`'s.
+    /// Each side keeps its own filters, and operations added afterwards apply
+    /// to the combined stream. Duplicates are not removed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let flagged: Vec<_> = LazyQuery::new(&orders)
+    ///     .where_(Order::total(), |&t| t > 10_000.0)
+    ///     .chain(LazyQuery::new(&archived_orders).where_(Order::disputed(), |&d| d))
+    ///     .collect();
+    /// ```
+    pub fn chain<I2>(self, other: LazyQuery<'a, T, I2>) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a>
+    where
+        I2: Iterator<Item = &'a T> + 'a,
+    {
+        LazyQuery {
+            iter: self.iter.chain(other.iter),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Yields every unordered pair of matching items once (lazy).
+    ///
+    /// Matching items are collected by reference when this is called, which
+    /// takes O(n) memory; the n·(n-1)/2 pairs themselves are produced on
+    /// demand, in `(i, j)` order with `i < j`, so `take`, `filter` or `find`
+    /// can stop early without materializing them.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let near_duplicate = LazyQuery::new(&records)
+    ///     .pairs()
+    ///     .find(|(a, b)| similarity(a, b) > 0.95);
+    /// ```
+    pub fn pairs(self) -> impl Iterator<Item = (&'a T, &'a T)> + 'a
+    where
+        I: 'a,
+    {
+        let items: Vec<&'a T> = self.iter.collect();
+        let n = items.len();
+        (0..n)
+            .flat_map(move |i| (i + 1..n).map(move |j| (i, j)))
+            .map(move |(i, j)| (items[i], items[j]))
+    }
+
+    /// Drops items whose key already appeared among the previous `window` items (lazy).
+    ///
+    /// Unlike a global distinct, a key may repeat once it has fallen out of the
+    /// sliding window, which makes this suitable for debouncing repeated
+    /// events. The window counts every item reaching this stage, including
+    /// suppressed ones, so a steady burst of the same key yields only its first
+    /// item. Items whose key-path yields no value are always kept. Memory is
+    /// bounded by `window` keys; a `window` of 0 keeps everything.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path identifying duplicates
+    /// * `window` - How many preceding items to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Ignore repeated alerts for the same host within the last 10 events
+    /// let alerts: Vec<_> = LazyQuery::new(&events)
+    ///     .dedup_window_by(Event::host(), 10)
+    ///     .collect();
+    /// ```
+    pub fn dedup_window_by<F>(self, key: KeyPaths<T, F>, window: usize) -> impl Iterator<Item = &'a T> + 'a
+    where
+        F: Eq + Hash + Clone + 'static,
+    {
+        let mut recent: VecDeque<Option<F>> = VecDeque::with_capacity(window);
+        let mut counts: HashMap<F, usize> = HashMap::new();
+        self.iter.filter(move |item| {
+            if window == 0 {
+                return true;
+            }
+            let current = key.get(item).cloned();
+            let duplicate = current.as_ref().is_some_and(|k| counts.contains_key(k));
+
+            if let Some(k) = &current {
+                *counts.entry(k.clone()).or_default() += 1;
+            }
+            recent.push_back(current);
+            if recent.len() > window {
+                if let Some(Some(expired)) = recent.pop_front() {
+                    if let Some(count) = counts.get_mut(&expired) {
+                        *count -= 1;
+                        if *count == 0 {
+                            counts.remove(&expired);
+                        }
+                    }
+                }
+            }
+            !duplicate
+        })
+    }
+
     /// Collects all items into a vector (terminal operation - executes query).
     ///
     /// # Example
@@ -252,6 +769,156 @@ where
         self.iter.fold(init, f)
     }
 
+    /// Clones the matching items into an existing `Vec` (terminal operation).
+    ///
+    /// Items are appended to `target` as they are found, with no intermediate
+    /// collection. Returns the number of items copied.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut restock = Vec::new();
+    /// let added = LazyQuery::new(&products)
+    ///     .where_(Product::stock(), |&s| s < 5)
+    ///     .copy_into(&mut restock);
+    /// ```
+    pub fn copy_into(self, target: &mut Vec<T>) -> usize
+    where
+        T: Clone,
+    {
+        let before = target.len();
+        target.extend(self.iter.cloned());
+        target.len() - before
+    }
+
+    /// Clones the matching items into a new owned `Vec` (terminal operation).
+    ///
+    /// Use this to narrow a large dataset once and then run many queries on
+    /// the subset: the returned `Vec` owns its items, so it can be queried
+    /// again with `.query()` or `.lazy_query()` (see `QueryExt` and
+    /// `QueryableExt`) independently of the original data.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let in_stock = LazyQuery::new(&products)
+    ///     .where_(Product::stock(), |&s| s > 0)
+    ///     .materialize();
+    /// let cheap = in_stock.query().where_(Product::price(), |&p| p < 10.0).count();
+    /// let by_category = in_stock.query().group_by(Product::category());
+    /// ```
+    pub fn materialize(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter.cloned().collect()
+    }
+
+    /// Groups references to the matching items by a field (terminal operation).
+    ///
+    /// Items are not cloned; each group keeps its items in iteration order.
+    /// Items whose key-path yields no value are left out.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_category: HashMap<String, Vec<&Product>> = LazyQuery::new(&products)
+    ///     .where_(Product::stock(), |&s| s > 0)
+    ///     .group_map(Product::category());
+    /// ```
+    pub fn group_map<K>(self, key: KeyPaths<T, K>) -> HashMap<K, Vec<&'a T>>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: HashMap<K, Vec<&'a T>> = HashMap::new();
+        for item in self.iter {
+            if let Some(k) = key.get(item).cloned() {
+                groups.entry(k).or_default().push(item);
+            }
+        }
+        groups
+    }
+
+    /// Collects references to the matching items sorted by a field (terminal operation).
+    ///
+    /// The sort is stable, so items with equal keys keep their original order.
+    /// Items are not cloned. Items whose key-path yields no value come first.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_name: Vec<&Product> = LazyQuery::new(&products)
+    ///     .where_(Product::stock(), |&s| s > 0)
+    ///     .sort_by_key(Product::name());
+    /// ```
+    pub fn sort_by_key<F>(self, path: KeyPaths<T, F>) -> Vec<&'a T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        let mut items: Vec<&'a T> = self.iter.collect();
+        items.sort_by_key(|item| path.get(item).cloned());
+        items
+    }
+
+    /// Collects references to the matching items sorted with a comparator (terminal operation).
+    ///
+    /// The sort is stable, so items that compare equal keep their original order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_price = LazyQuery::new(&products)
+    ///     .sort_by(|a, b| a.price.total_cmp(&b.price));
+    /// ```
+    pub fn sort_by<C>(self, cmp: C) -> Vec<&'a T>
+    where
+        C: FnMut(&&'a T, &&'a T) -> std::cmp::Ordering,
+    {
+        let mut items: Vec<&'a T> = self.iter.collect();
+        items.sort_by(cmp);
+        items
+    }
+
+    /// Collects the matching items and passes them to a function (terminal operation).
+    ///
+    /// Useful for custom post-processing over the whole result set while
+    /// keeping the fluent style.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Prices normalized to the most expensive match
+    /// let normalized = LazyQuery::new(&products)
+    ///     .where_(Product::stock(), |&s| s > 0)
+    ///     .pipe(|items| {
+    ///         let max = items.iter().map(|p| p.price).fold(0.0, f64::max);
+    ///         items.iter().map(|p| p.price / max).collect::<Vec<_>>()
+    ///     });
+    /// ```
+    pub fn pipe<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(Vec<&'a T>) -> R,
+    {
+        f(self.iter.collect())
+    }
+
+    /// Reduces matching items pairwise to a single item (terminal operation).
+    ///
+    /// Unlike `fold`, no initial value is needed. Returns `None` if no items match.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Cheapest product, keeping the first one on ties
+    /// let cheapest = query.reduce(|best, item| if item.price < best.price { item } else { best });
+    /// ```
+    pub fn reduce<F>(self, f: F) -> Option<&'a T>
+    where
+        F: FnMut(&'a T, &'a T) -> &'a T,
+    {
+        self.iter.reduce(f)
+    }
+
     /// Finds an item matching a predicate (terminal - short-circuits).
     ///
     /// # Example
@@ -305,6 +972,29 @@ where
     pub fn into_iter(self) -> I {
         self.iter
     }
+
+    /// Erases the iterator type so queries built differently share one type.
+    ///
+    /// Each filter step produces a distinct `impl Iterator` type; boxing lets
+    /// such queries be stored together, e.g. to pass them to [`interleave`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sources = vec![
+    ///     LazyQuery::new(&books).where_(Item::kind(), |k| k == "book").boxed(),
+    ///     LazyQuery::new(&films).boxed(),
+    /// ];
+    /// ```
+    pub fn boxed(self) -> LazyQuery<'a, T, Box<dyn Iterator<Item = &'a T> + 'a>>
+    where
+        I: 'a,
+    {
+        LazyQuery {
+            iter: Box::new(self.iter),
+            _phantom: PhantomData,
+        }
+    }
 }
 
 // Aggregation operations
@@ -466,6 +1156,68 @@ where
     ) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a> {
         self.where_(path, move |time| time >= &start && time <= &end)
     }
+
+    /// Filter by SystemTime being within a duration before now (lazy).
+    ///
+    /// Keeps times after `SystemTime::now() - window`; like the i64 helpers,
+    /// there is no upper bound, so future times also match. If the cutoff
+    /// would precede the earliest representable time, every item matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `window` - How far back from now to look
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent: Vec<_> = LazyQuery::new(&events)
+    ///     .where_within_systemtime(Event::timestamp(), Duration::from_secs(15 * 60))
+    ///     .collect();
+    /// ```
+    pub fn where_within_systemtime(self, path: KeyPaths<T, SystemTime>, window: Duration) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a> {
+        let cutoff = SystemTime::now().checked_sub(window);
+        self.where_(path, move |time| match cutoff {
+            Some(cutoff) => *time > cutoff,
+            None => true,
+        })
+    }
+
+    /// Filter by SystemTime being within the last N days from now (lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `days` - Number of days to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent: Vec<_> = LazyQuery::new(&events)
+    ///     .where_last_days_systemtime(Event::timestamp(), 7)
+    ///     .collect();
+    /// ```
+    pub fn where_last_days_systemtime(self, path: KeyPaths<T, SystemTime>, days: u64) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a> {
+        self.where_within_systemtime(path, Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+    }
+
+    /// Filter by SystemTime being within the last N hours from now (lazy).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `hours` - Number of hours to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent: Vec<_> = LazyQuery::new(&events)
+    ///     .where_last_hours_systemtime(Event::timestamp(), 24)
+    ///     .collect();
+    /// ```
+    pub fn where_last_hours_systemtime(self, path: KeyPaths<T, SystemTime>, hours: u64) -> LazyQuery<'a, T, impl Iterator<Item = &'a T> + 'a> {
+        self.where_within_systemtime(path, Duration::from_secs(hours.saturating_mul(60 * 60)))
+    }
 }
 
 // DateTime operations with chrono (only available with datetime feature, lazy)
@@ -993,6 +1745,48 @@ where
     }
 }
 
+/// Merges several lazy queries round-robin, one item from each in turn.
+///
+/// Pulls the next item from each query in rotation; a query that runs out
+/// is dropped from the rotation and the rest continue until all are
+/// exhausted. Nothing is pulled from a query before its turn, so `take`
+/// on the result only advances each source as far as needed. Use
+/// [`LazyQuery::boxed`] to bring differently filtered queries to one type.
+///
+/// # Example
+///
+/// ```ignore
+/// // One result from each category in turn
+/// let mixed: Vec<_> = interleave(vec![
+///     LazyQuery::new(&news).boxed(),
+///     LazyQuery::new(&videos).where_(Video::length(), |&l| l < 600).boxed(),
+/// ])
+/// .take(20)
+/// .collect();
+/// ```
+pub fn interleave<'a, T: 'static>(
+    queries: Vec<LazyQuery<'a, T, Box<dyn Iterator<Item = &'a T> + 'a>>>,
+) -> impl Iterator<Item = &'a T> + 'a {
+    let mut sources: Vec<_> = queries.into_iter().map(|query| query.iter).collect();
+    let mut turn = 0;
+    std::iter::from_fn(move || {
+        while !sources.is_empty() {
+            if turn >= sources.len() {
+                turn = 0;
+            }
+            match sources[turn].next() {
+                Some(item) => {
+                    turn += 1;
+                    return Some(item);
+                }
+                // Removing keeps the order, so `turn` now names the next source.
+                None => drop(sources.remove(turn)),
+            }
+        }
+        None
+    })
+}
+
 // Enable using LazyQuery in for loops
 impl<'a, T: 'static, I> IntoIterator for LazyQuery<'a, T, I>
 where
@@ -1005,3 +1799,451 @@ where
         self.iter
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use key_paths_derive::Keypath;
+    use std::cell::Cell;
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Product {
+        id: u32,
+        name: String,
+        price: f64,
+        cost: f64,
+    }
+
+    fn products() -> Vec<Product> {
+        vec![
+            Product { id: 1, name: "Laptop".to_string(), price: 999.0, cost: 700.0 },
+            Product { id: 2, name: "Mouse".to_string(), price: 20.0, cost: 25.0 },
+            Product { id: 3, name: "Monitor".to_string(), price: 300.0, cost: 180.0 },
+            Product { id: 4, name: "Cable".to_string(), price: 5.0, cost: 5.0 },
+        ]
+    }
+
+    #[test]
+    fn test_comparison_filters() {
+        fn ids<'a>(query: LazyQuery<'a, Product, impl Iterator<Item = &'a Product> + 'a>) -> Vec<u32> {
+            query.map_items(|p| p.id).collect()
+        }
+
+        let products = products();
+
+        assert_eq!(ids(LazyQuery::new(&products).where_gt(Product::price(), 20.0)), vec![1, 3]);
+        assert_eq!(ids(LazyQuery::new(&products).where_ge(Product::price(), 20.0)), vec![1, 2, 3]);
+        assert_eq!(ids(LazyQuery::new(&products).where_lt(Product::price(), 20.0)), vec![4]);
+        assert_eq!(ids(LazyQuery::new(&products).where_le(Product::price(), 20.0)), vec![2, 4]);
+        assert_eq!(ids(LazyQuery::new(&products).where_eq(Product::id(), 3)), vec![3]);
+        assert_eq!(ids(LazyQuery::new(&products).where_ne(Product::id(), 3)), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_comparison_filters_float_equality_is_exact() {
+        let products = vec![Product { id: 1, name: "Odd".to_string(), price: 0.1 + 0.2, cost: 0.0 }];
+
+        assert_eq!(LazyQuery::new(&products).where_eq(Product::price(), 0.3).count(), 0);
+        assert_eq!(LazyQuery::new(&products).where_ne(Product::price(), 0.3).count(), 1);
+    }
+
+    #[test]
+    fn test_where_item() {
+        let products = products();
+
+        let profitable: Vec<u32> = LazyQuery::new(&products)
+            .where_item(|p| p.price > p.cost)
+            .map_items(|p| p.id)
+            .collect();
+        assert_eq!(profitable, vec![1, 3]);
+
+        let cheap_and_profitable = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p < 500.0)
+            .where_item(|p| p.price > p.cost)
+            .count();
+        assert_eq!(cheap_and_profitable, 1);
+    }
+
+    #[test]
+    fn test_product_with() {
+        let products = products();
+        let tiers = ["basic", "pro"];
+
+        let pairs: Vec<(u32, &str)> = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p > 100.0)
+            .product_with(&tiers, |product, tier| (product.id, *tier))
+            .collect();
+
+        assert_eq!(pairs, vec![(1, "basic"), (1, "pro"), (3, "basic"), (3, "pro")]);
+    }
+
+    #[test]
+    fn test_product_with_take_is_lazy() {
+        let products = products();
+        let tiers = [1, 2, 3];
+        let calls = Cell::new(0);
+
+        let first: Vec<(u32, i32)> = LazyQuery::new(&products)
+            .product_with(&tiers, |product, tier| {
+                calls.set(calls.get() + 1);
+                (product.id, *tier)
+            })
+            .take(4)
+            .collect();
+
+        assert_eq!(first, vec![(1, 1), (1, 2), (1, 3), (2, 1)]);
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let products = products();
+
+        let best_margin = LazyQuery::new(&products)
+            .reduce(|best, p| if p.price - p.cost > best.price - best.cost { p } else { best });
+        assert_eq!(best_margin.map(|p| p.id), Some(1));
+
+        let cheapest_under_500 = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p < 500.0)
+            .reduce(|best, p| if p.price < best.price { p } else { best });
+        assert_eq!(cheapest_under_500.map(|p| p.id), Some(4));
+
+        let none = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p > 10_000.0)
+            .reduce(|best, _| best);
+        assert!(none.is_none());
+    }
+
+    #[test]
+    fn test_copy_into_appends_matches() {
+        let products = products();
+        let mut target = vec![products[3].clone()];
+
+        let copied = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p >= 20.0)
+            .copy_into(&mut target);
+
+        assert_eq!(copied, 3);
+        assert_eq!(target.iter().map(|p| p.id).collect::<Vec<_>>(), vec![4, 1, 2, 3]);
+        assert_eq!(products.len(), 4);
+    }
+
+    #[test]
+    fn test_pipe_receives_filtered_items() {
+        let products = products();
+
+        let (ids, margin) = LazyQuery::new(&products)
+            .where_item(|p| p.price > p.cost)
+            .pipe(|items| {
+                let ids: Vec<u32> = items.iter().map(|p| p.id).collect();
+                let margin: f64 = items.iter().map(|p| p.price - p.cost).sum();
+                (ids, margin)
+            });
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(margin, 419.0);
+
+        let empty = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p < 0.0)
+            .pipe(|items| items.len());
+        assert_eq!(empty, 0);
+    }
+
+    #[test]
+    fn test_sort_terminals_are_stable_and_borrow() {
+        let products = vec![
+            Product { id: 1, name: "B".to_string(), price: 5.0, cost: 1.0 },
+            Product { id: 2, name: "A".to_string(), price: 3.0, cost: 1.0 },
+            Product { id: 3, name: "B".to_string(), price: 1.0, cost: 1.0 },
+            Product { id: 4, name: "A".to_string(), price: 9.0, cost: 1.0 },
+        ];
+
+        let by_name = LazyQuery::new(&products).sort_by_key(Product::name());
+        assert_eq!(by_name.iter().map(|p| p.id).collect::<Vec<_>>(), vec![2, 4, 1, 3]);
+        assert!(std::ptr::eq(by_name[0], &products[1]));
+
+        let by_cost_desc = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p > 2.0)
+            .sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        assert_eq!(by_cost_desc.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert!(std::ptr::eq(by_cost_desc[2], &products[3]));
+    }
+
+    #[test]
+    fn test_where_fields_cmp_and_eq() {
+        use std::cmp::Ordering;
+
+        let products = products();
+        let ids = |items: Vec<&Product>| items.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        let below_cost = LazyQuery::new(&products)
+            .where_fields_cmp(Product::price(), Product::cost(), Ordering::Less)
+            .collect();
+        assert_eq!(ids(below_cost), vec![2]);
+
+        let at_cost = LazyQuery::new(&products)
+            .where_fields_eq(Product::price(), Product::cost())
+            .collect();
+        assert_eq!(ids(at_cost), vec![4]);
+
+        // A missing value on either side excludes the item.
+        let cost_if_profitable =
+            KeyPaths::failable_readable(|p: &Product| if p.price > p.cost { Some(&p.cost) } else { None });
+        let missing = LazyQuery::new(&products)
+            .where_fields_cmp(Product::price(), cost_if_profitable, Ordering::Greater)
+            .collect();
+        assert_eq!(ids(missing), vec![1, 3]);
+        let missing_eq = LazyQuery::new(&products)
+            .where_fields_eq(Product::price(), KeyPaths::failable_readable(|_: &Product| None::<&f64>))
+            .count();
+        assert_eq!(missing_eq, 0);
+    }
+
+    #[test]
+    fn test_group_map_groups_references() {
+        let products = products();
+        let margin_band = KeyPaths::failable_readable(|p: &Product| {
+            if p.price > p.cost {
+                Some(if p.price - p.cost > 150.0 { &"high" } else { &"low" })
+            } else {
+                None
+            }
+        });
+
+        let groups = LazyQuery::new(&products).group_map(margin_band);
+        // The mouse and cable have no margin band and are excluded.
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["high"].iter().map(|p| p.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(groups["low"].iter().map(|p| p.id).collect::<Vec<_>>(), vec![3]);
+        assert!(std::ptr::eq(groups["high"][0], &products[0]));
+
+        let by_name = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p < 100.0)
+            .group_map(Product::name());
+        assert_eq!(by_name.len(), 2);
+        assert!(by_name.contains_key("Mouse") && by_name.contains_key("Cable"));
+    }
+
+    #[derive(Keypath)]
+    struct Ping {
+        id: u32,
+        at: SystemTime,
+    }
+
+    #[test]
+    fn test_relative_systemtime_filters() {
+        let now = SystemTime::now();
+        let hours = |h: u64| Duration::from_secs(h * 60 * 60);
+        let pings = vec![
+            Ping { id: 1, at: now - hours(2) },
+            Ping { id: 2, at: now - hours(50) },
+            Ping { id: 3, at: now - Duration::from_secs(10) },
+        ];
+        let ids = |items: Vec<&Ping>| items.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(LazyQuery::new(&pings).where_last_hours_systemtime(Ping::at(), 3).collect()), vec![1, 3]);
+        assert_eq!(ids(LazyQuery::new(&pings).where_last_days_systemtime(Ping::at(), 3).collect()), vec![1, 2, 3]);
+        assert_eq!(
+            ids(LazyQuery::new(&pings).where_within_systemtime(Ping::at(), Duration::from_secs(60)).collect()),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_materialize_can_be_queried_again() {
+        use crate::{QueryExt, QueryableExt};
+
+        let products = products();
+        let profitable = LazyQuery::new(&products)
+            .where_item(|p| p.price > p.cost)
+            .materialize();
+        assert_eq!(profitable, vec![products[0].clone(), products[2].clone()]);
+
+        let direct = LazyQuery::new(&products)
+            .where_item(|p| p.price > p.cost)
+            .where_(Product::price(), |&p| p < 500.0)
+            .count();
+        let requeried = profitable.query().where_(Product::price(), |&p| p < 500.0).count();
+        assert_eq!(requeried, direct);
+        assert_eq!(profitable.lazy_query().count(), profitable.query().count());
+    }
+
+    #[test]
+    fn test_where_stateful_stops_once_budget_exceeded() {
+        let products = products();
+        let budget = 1100.0;
+
+        // Running totals: 999, 1019, 1319 (over budget), then nothing more is kept.
+        let within_budget: Vec<u32> = LazyQuery::new(&products)
+            .where_stateful((0.0, false), |(spent, exceeded), p: &Product| {
+                if !*exceeded {
+                    *spent += p.price;
+                    *exceeded = *spent > budget;
+                }
+                !*exceeded
+            })
+            .map_items(|p| p.id)
+            .collect();
+        assert_eq!(within_budget, vec![1, 2]);
+
+        // Every second item, counted after the earlier filter.
+        let alternate: Vec<u32> = LazyQuery::new(&products)
+            .where_(Product::price(), |&p| p < 500.0)
+            .where_stateful(0usize, |seen, _| {
+                *seen += 1;
+                *seen % 2 == 1
+            })
+            .map_items(|p| p.id)
+            .collect();
+        assert_eq!(alternate, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_chain_keeps_each_side_filters_in_order() {
+        let products = products();
+        let archived = vec![
+            Product { id: 10, name: "Tablet".to_string(), price: 450.0, cost: 300.0 },
+            Product { id: 11, name: "Stylus".to_string(), price: 15.0, cost: 4.0 },
+        ];
+
+        let ids: Vec<u32> = LazyQuery::new(&products)
+            .where_item(|p| p.price <= p.cost)
+            .chain(LazyQuery::new(&archived).where_(Product::price(), |&p| p > 100.0))
+            .map_items(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![2, 4, 10]);
+
+        let cheap = LazyQuery::new(&products)
+            .chain(LazyQuery::new(&archived))
+            .where_(Product::price(), |&p| p < 25.0)
+            .count();
+        assert_eq!(cheap, 3);
+    }
+
+    #[test]
+    fn test_where_valid_at_lazy() {
+        #[derive(Keypath)]
+        struct Assignment {
+            owner: &'static str,
+            from: i64,
+            to: i64,
+        }
+
+        let assignments = vec![
+            Assignment { owner: "ann", from: 10, to: 20 },
+            Assignment { owner: "bob", from: 21, to: i64::MAX },
+        ];
+        let owner_at = |at| -> Vec<&str> {
+            LazyQuery::new(&assignments)
+                .where_valid_at(Assignment::from(), Assignment::to(), at)
+                .map_items(|a| a.owner)
+                .collect()
+        };
+
+        assert_eq!(owner_at(15), vec!["ann"]);
+        assert_eq!(owner_at(1_000_000), vec!["bob"]);
+        assert!(owner_at(5).is_empty());
+    }
+
+    #[test]
+    fn test_take_until_sum_boundaries() {
+        let products = products();
+        let ids = |items: Vec<&Product>| items.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        // Running totals: 999, 1019, 1319, 1324.
+        assert_eq!(ids(LazyQuery::new(&products).take_until_sum(Product::price(), 1100.0)), vec![1, 2]);
+        assert_eq!(
+            ids(LazyQuery::new(&products).take_until_sum_inclusive(Product::price(), 1100.0)),
+            vec![1, 2, 3]
+        );
+
+        // Landing exactly on the budget does not cross it.
+        assert_eq!(ids(LazyQuery::new(&products).take_until_sum(Product::price(), 1019.0)), vec![1, 2]);
+        assert_eq!(
+            ids(LazyQuery::new(&products).take_until_sum_inclusive(Product::price(), 1019.0)),
+            vec![1, 2, 3]
+        );
+
+        assert!(LazyQuery::new(&products).take_until_sum(Product::price(), 100.0).is_empty());
+        assert_eq!(ids(LazyQuery::new(&products).take_until_sum_inclusive(Product::price(), 100.0)), vec![1]);
+        assert_eq!(LazyQuery::new(&products).take_until_sum(Product::price(), 1e9).len(), 4);
+    }
+
+    #[test]
+    fn test_pairs_yields_each_unordered_pair_once() {
+        let products = products();
+        let ids: Vec<(u32, u32)> = LazyQuery::new(&products).pairs().map(|(a, b)| (a.id, b.id)).collect();
+        assert_eq!(ids, vec![(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)]);
+
+        let n = products.len();
+        let unique: std::collections::HashSet<(u32, u32)> =
+            ids.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+        assert_eq!(unique.len(), n * (n - 1) / 2);
+
+        let cheap = LazyQuery::new(&products).where_(Product::price(), |&p| p < 500.0);
+        assert_eq!(cheap.pairs().count(), 3);
+        assert_eq!(LazyQuery::new(&products[..1]).pairs().count(), 0);
+
+        // Closest prices among the first pairs, stopping early.
+        let closest = LazyQuery::new(&products)
+            .pairs()
+            .take(3)
+            .min_by(|(a, b), (c, d)| (a.price - b.price).abs().total_cmp(&(c.price - d.price).abs()))
+            .map(|(a, b)| (a.id, b.id));
+        assert_eq!(closest, Some((1, 3)));
+    }
+
+    #[test]
+    fn test_dedup_window_by_suppresses_only_within_window() {
+        let keys = ["a", "b", "a", "c", "d", "a", "a", "a"];
+        let events: Vec<Product> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| Product { id: i as u32 + 1, name: k.to_string(), price: 1.0, cost: 1.0 })
+            .collect();
+
+        let kept: Vec<u32> = LazyQuery::new(&events)
+            .dedup_window_by(Product::name(), 2)
+            .map(|p| p.id)
+            .collect();
+        // #3 repeats "a" two items after #1 and is dropped; #6 is three items
+        // after #3 and is kept; #7 and #8 fall inside the window of #6.
+        assert_eq!(kept, vec![1, 2, 4, 5, 6]);
+
+        // A window of 0 suppresses nothing; a large one behaves like distinct.
+        assert_eq!(LazyQuery::new(&events).dedup_window_by(Product::name(), 0).count(), 8);
+        let distinct: Vec<u32> = LazyQuery::new(&events)
+            .dedup_window_by(Product::name(), 100)
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(distinct, vec![1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_interleave_round_robin_across_uneven_sources() {
+        let products = products();
+        let ids = |items: Vec<&Product>| items.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        let mixed = interleave(vec![
+            LazyQuery::new(&products).boxed(),
+            LazyQuery::new(&products[..1]).boxed(),
+            LazyQuery::new(&products).where_(Product::price(), |&p| p < 100.0).boxed(),
+        ]);
+        // Sources yield [1, 2, 3, 4], [1] and [2, 4].
+        assert_eq!(ids(mixed.collect()), vec![1, 1, 2, 2, 4, 3, 4]);
+
+        // Sources are only advanced on their turn.
+        let pulled = Cell::new(0);
+        let counted = LazyQuery::new(&products)
+            .where_item(|_| {
+                pulled.set(pulled.get() + 1);
+                true
+            })
+            .boxed();
+        let first_two: Vec<&Product> = interleave(vec![counted, LazyQuery::new(&products).boxed()]).take(2).collect();
+        assert_eq!(ids(first_two), vec![1, 1]);
+        assert_eq!(pulled.get(), 1);
+
+        assert_eq!(interleave::<Product>(Vec::new()).count(), 0);
+        assert_eq!(interleave(vec![LazyQuery::new(&products[..0]).boxed()]).count(), 0);
+    }
+}
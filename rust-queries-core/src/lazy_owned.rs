@@ -0,0 +1,339 @@
+//! Lazy queries over iterators of owned items.
+//!
+//! This module provides `LazyOwnedQuery` for data that is generated or streamed
+//! on the fly rather than stored in a slice. Items are filtered and projected as
+//! they are produced; only the ones you keep are materialized.
+
+use key_paths_core::KeyPaths;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// A lazy query over an iterator that yields owned items.
+///
+/// Works like `LazyQuery`, but takes items by value so no backing slice is needed.
+///
+/// # Example
+///
+/// ```ignore
+/// let readings = (0..1_000_000).map(|i| Reading { sensor: i % 8, value: sample(i) });
+///
+/// // Nothing is generated until `collect()` runs, and it stops after 10 matches
+/// let spikes: Vec<Reading> = LazyOwnedQuery::new(readings)
+///     .where_(Reading::value(), |&v| v > 99.0)
+///     .take(10)
+///     .collect();
+/// ```
+pub struct LazyOwnedQuery<T, I>
+where
+    I: Iterator<Item = T>,
+{
+    iter: I,
+}
+
+impl<T, I> LazyOwnedQuery<T, I>
+where
+    I: Iterator<Item = T>,
+{
+    /// Creates a new lazy query from anything that yields owned items.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = LazyOwnedQuery::new((1..=100).map(|id| User::generate(id)));
+    /// ```
+    pub fn new<C>(items: C) -> Self
+    where
+        C: IntoIterator<Item = T, IntoIter = I>,
+    {
+        Self {
+            iter: items.into_iter(),
+        }
+    }
+
+    /// Adds a filter predicate using a key-path (lazy - not executed yet).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let adults = query.where_(User::age(), |&age| age >= 18);
+    /// ```
+    pub fn where_<F, P>(self, path: KeyPaths<T, F>, predicate: P) -> LazyOwnedQuery<T, impl Iterator<Item = T>>
+    where
+        F: 'static,
+        P: Fn(&F) -> bool,
+    {
+        LazyOwnedQuery {
+            iter: self.iter.filter(move |item| {
+                path.get(item).is_some_and(&predicate)
+            }),
+        }
+    }
+
+    /// Adds a filter predicate over the whole item (lazy).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let profitable = query.where_item(|p| p.price > p.cost);
+    /// ```
+    pub fn where_item<P>(self, predicate: P) -> LazyOwnedQuery<T, impl Iterator<Item = T>>
+    where
+        P: Fn(&T) -> bool,
+    {
+        LazyOwnedQuery {
+            iter: self.iter.filter(move |item| predicate(item)),
+        }
+    }
+
+    /// Takes at most `n` matching items (lazy).
+    ///
+    /// The source iterator is not advanced past the `n`-th match.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first_ten = query.take(10);
+    /// ```
+    pub fn take(self, n: usize) -> LazyOwnedQuery<T, impl Iterator<Item = T>> {
+        LazyOwnedQuery {
+            iter: self.iter.take(n),
+        }
+    }
+
+    /// Projects a field from each item (lazy).
+    ///
+    /// Items whose key-path yields no value are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let names: Vec<String> = query.select(User::name()).collect();
+    /// ```
+    pub fn select<F>(self, path: KeyPaths<T, F>) -> impl Iterator<Item = F>
+    where
+        F: Clone + 'static,
+    {
+        self.iter.filter_map(move |item| path.get(&item).cloned())
+    }
+
+    /// Collects the matching items (terminal operation).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results: Vec<User> = query.collect();
+    /// ```
+    pub fn collect(self) -> Vec<T> {
+        self.iter.collect()
+    }
+
+    /// Moves the matching items into an existing `Vec` (terminal operation).
+    ///
+    /// Items are appended to `target` as they are produced, with no
+    /// intermediate collection. Returns the number of items moved.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut alerts = Vec::new();
+    /// let added = query.where_(Reading::value(), |&v| v > 99.0).drain_into(&mut alerts);
+    /// ```
+    pub fn drain_into(self, target: &mut Vec<T>) -> usize {
+        let before = target.len();
+        target.extend(self.iter);
+        target.len() - before
+    }
+
+    /// Keeps the `k` items with the largest key, largest first (terminal operation).
+    ///
+    /// Consumes the iterator while holding at most `k + 1` items in a bounded
+    /// heap, so the source never has to fit in memory. Ties keep the item that
+    /// came first. Items whose key-path yields no value are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // The 100 largest orders in a stream
+    /// let largest = LazyOwnedQuery::new(order_stream).into_top_k(Order::total_cents(), 100);
+    /// ```
+    pub fn into_top_k<F>(self, path: KeyPaths<T, F>, k: usize) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap: BinaryHeap<Reverse<Ranked<F, T>>> = BinaryHeap::with_capacity(k + 1);
+        for (seq, item) in self.iter.enumerate() {
+            let Some(key) = path.get(&item).cloned() else {
+                continue;
+            };
+            heap.push(Reverse(Ranked { key, seq, item }));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // Ascending order of `Reverse` is descending order of rank.
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| ranked.item)
+            .collect()
+    }
+
+    /// Gets the first matching item (terminal operation).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first = query.first();
+    /// ```
+    pub fn first(mut self) -> Option<T> {
+        self.iter.next()
+    }
+
+    /// Counts the matching items (terminal operation).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let count = query.count();
+    /// ```
+    pub fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+impl<T, I> IntoIterator for LazyOwnedQuery<T, I>
+where
+    I: Iterator<Item = T>,
+{
+    type Item = T;
+    type IntoIter = I;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+/// Heap entry ranked by key, with earlier items ranking higher on ties.
+struct Ranked<F, T> {
+    key: F,
+    seq: usize,
+    item: T,
+}
+
+impl<F: Ord, T> Ord for Ranked<F, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key
+            .cmp(&other.key)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl<F: Ord, T> PartialOrd for Ranked<F, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Ord, T> PartialEq for Ranked<F, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Ord, T> Eq for Ranked<F, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use key_paths_derive::Keypath;
+    use std::cell::Cell;
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Reading {
+        id: u32,
+        sensor: String,
+        value: u32,
+    }
+
+    fn reading(id: u32) -> Reading {
+        Reading {
+            id,
+            sensor: format!("s{}", id % 3),
+            value: id * 7 % 50,
+        }
+    }
+
+    #[test]
+    fn test_filter_generated_range() {
+        let results = LazyOwnedQuery::new((0..100).map(reading))
+            .where_(Reading::sensor(), |s| s == "s1")
+            .where_(Reading::value(), |&v| v > 40)
+            .collect();
+
+        let expected: Vec<Reading> = (0..100)
+            .map(reading)
+            .filter(|r| r.sensor == "s1" && r.value > 40)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(results, expected);
+
+        let ids: Vec<u32> = LazyOwnedQuery::new((0..100).map(reading))
+            .where_item(|r| r.value == 0)
+            .select(Reading::id())
+            .collect();
+        assert_eq!(ids, vec![0, 50]);
+
+        assert_eq!(LazyOwnedQuery::new((0..100).map(reading)).where_(Reading::value(), |&v| v > 100).first(), None);
+        assert_eq!(LazyOwnedQuery::new(vec![reading(1), reading(2)]).count(), 2);
+    }
+
+    #[test]
+    fn test_take_stops_generating() {
+        let generated = Cell::new(0);
+        let results = LazyOwnedQuery::new((0..1000).map(|id| {
+            generated.set(generated.get() + 1);
+            reading(id)
+        }))
+        .where_(Reading::sensor(), |s| s == "s0")
+        .take(3)
+        .collect();
+
+        assert_eq!(results.iter().map(|r| r.id).collect::<Vec<_>>(), vec![0, 3, 6]);
+        assert_eq!(generated.get(), 7);
+    }
+
+    #[test]
+    fn test_drain_into_appends_matches() {
+        let mut target = vec![reading(1000)];
+        let moved = LazyOwnedQuery::new((0..30).map(reading))
+            .where_(Reading::sensor(), |s| s == "s2")
+            .drain_into(&mut target);
+
+        assert_eq!(moved, 10);
+        assert_eq!(target.len(), 11);
+        assert_eq!(target[0].id, 1000);
+        assert!(target[1..].iter().all(|r| r.sensor == "s2"));
+    }
+
+    #[test]
+    fn test_into_top_k_matches_full_sort() {
+        let top = LazyOwnedQuery::new((0..500).map(reading))
+            .where_(Reading::sensor(), |s| s != "s0")
+            .into_top_k(Reading::value(), 25);
+
+        let mut expected: Vec<Reading> = (0..500)
+            .map(reading)
+            .filter(|r| r.sensor != "s0")
+            .collect();
+        expected.sort_by_key(|r| Reverse(r.value));
+        expected.truncate(25);
+
+        assert_eq!(top, expected);
+        assert!(LazyOwnedQuery::new((0..10).map(reading)).into_top_k(Reading::value(), 0).is_empty());
+        assert_eq!(LazyOwnedQuery::new((0..3).map(reading)).into_top_k(Reading::value(), 10).len(), 3);
+    }
+}
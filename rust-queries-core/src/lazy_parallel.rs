@@ -94,6 +94,22 @@ impl<'a, T: 'static + Send + Sync> LazyParallelQuery<'a, T> {
         self
     }
 
+    /// Adds a filter predicate over the whole item (lazy).
+    ///
+    /// The predicate only needs to be `Send + Sync`, which makes it the way to
+    /// combine several fields or capture shared state such as atomics.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = LazyParallelQuery::new(&products)
+    ///     .where_item(|p| p.price > p.cost);
+    /// ```
+    pub fn where_item(mut self, predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
     /// Collects all items into a vector (terminal operation - executes query in parallel).
     ///
     /// # Example
@@ -110,6 +126,10 @@ impl<'a, T: 'static + Send + Sync> LazyParallelQuery<'a, T> {
 
     /// Gets the first item (terminal operation - executes until first match in parallel).
     ///
+    /// Returns whichever match a worker finds first, not necessarily the
+    /// earliest one in the slice. Workers stop picking up new items once a
+    /// match is found.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -137,6 +157,9 @@ impl<'a, T: 'static + Send + Sync> LazyParallelQuery<'a, T> {
 
     /// Checks if any items match (terminal operation - short-circuits in parallel).
     ///
+    /// As soon as one worker finds a match, the others stop evaluating filters
+    /// for further items, so an early match avoids scanning the whole slice.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -208,6 +231,9 @@ impl<'a, T: 'static + Send + Sync> LazyParallelQuery<'a, T> {
 
     /// Finds an item matching a predicate (terminal - short-circuits in parallel).
     ///
+    /// Like `first_parallel`, any match may be returned and the remaining
+    /// workers stop once one is found.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -411,6 +437,42 @@ impl<'a, T: 'static + Send + Sync> LazyParallelQuery<'a, T> {
             .filter_map(|item| path.get(item).cloned())
             .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
     }
+
+    /// Estimates the number of distinct values of a field (terminal operation in parallel).
+    ///
+    /// Each thread fills its own HyperLogLog sketch and the sketches are
+    /// merged, so the estimate matches `Query::approx_count_distinct` on the
+    /// same data: about 0.8% relative standard error in 16 KiB per sketch.
+    ///
+    /// Like `where_item`, the field is read by a `Send + Sync` closure rather
+    /// than a key-path, so it can run on every worker thread. Return `None`
+    /// to leave an item out.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let visitors = LazyParallelQuery::new(&page_views)
+    ///     .approx_count_distinct_parallel(|view: &PageView| Some(&view.user_id));
+    /// ```
+    #[cfg(feature = "hll")]
+    pub fn approx_count_distinct_parallel<F, E>(&self, value: E) -> u64
+    where
+        F: std::hash::Hash + Send + Sync + 'static,
+        E: Fn(&T) -> Option<&F> + Send + Sync,
+    {
+        use crate::hll::HyperLogLog;
+
+        self.data
+            .par_iter()
+            .filter(|item| self.filters.iter().all(|f| f(item)))
+            .filter_map(&value)
+            .fold(HyperLogLog::new, |mut sketch, value| {
+                sketch.insert(value);
+                sketch
+            })
+            .reduce(HyperLogLog::new, HyperLogLog::merge)
+            .estimate()
+    }
 }
 
 // DateTime operations for SystemTime (parallel)
@@ -1025,3 +1087,77 @@ impl<T: 'static + Send + Sync> LazyParallelQueryExt<T> for Vec<T> {
         LazyParallelQuery::new(self)
     }
 }
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const N: usize = 1_000_000;
+
+    /// Runs `f` on a dedicated pool with several workers, whatever the host's core count.
+    fn on_workers<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap()
+            .install(f)
+    }
+
+    #[test]
+    fn test_any_parallel_stops_after_early_match() {
+        let data: Vec<usize> = (0..N).collect();
+        let evaluated = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&evaluated);
+        let found = on_workers(|| {
+            LazyParallelQuery::new(&data)
+                .where_item(move |&v| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    v % 1000 == 0
+                })
+                .any_parallel()
+        });
+
+        // Every worker meets a match within 1000 items, so all of them stop early.
+        assert!(found);
+        let evaluated = evaluated.load(Ordering::Relaxed);
+        assert!(evaluated < N / 10, "evaluated {} of {} items", evaluated, N);
+    }
+
+    #[test]
+    fn test_find_parallel_stops_after_early_match() {
+        let data: Vec<usize> = (0..N).collect();
+        let evaluated = AtomicUsize::new(0);
+
+        let found = on_workers(|| {
+            LazyParallelQuery::new(&data).find_parallel(|&&v| {
+                evaluated.fetch_add(1, Ordering::Relaxed);
+                v % 1000 == 0
+            })
+        });
+
+        assert_eq!(found.map(|v| v % 1000), Some(0));
+        let evaluated = evaluated.load(Ordering::Relaxed);
+        assert!(evaluated < N / 10, "evaluated {} of {} items", evaluated, N);
+    }
+
+    #[test]
+    #[cfg(feature = "hll")]
+    fn test_approx_count_distinct_parallel_matches_sequential() {
+        let data: Vec<u64> = (0..200_000u64).map(|i| i % 50_000).collect();
+
+        let parallel = LazyParallelQuery::new(&data).approx_count_distinct_parallel(|v: &u64| Some(v));
+        let sequential = crate::query::Query::new(&data).approx_count_distinct(KeyPaths::readable(|v: &u64| v));
+
+        let error = (parallel as f64 - 50_000.0).abs() / 50_000.0;
+        assert!(error < 0.03, "estimate {} off by {:.2}%", parallel, error * 100.0);
+        assert_eq!(parallel, sequential);
+
+        let odd = LazyParallelQuery::new(&data)
+            .where_item(|v| v % 2 == 1)
+            .approx_count_distinct_parallel(|v: &u64| Some(v));
+        assert!((odd as f64 - 25_000.0).abs() / 25_000.0 < 0.03);
+    }
+}
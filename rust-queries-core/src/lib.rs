@@ -42,8 +42,12 @@
 //! ```
 
 pub mod query;
+pub mod plan;
+pub mod prepared;
+pub mod sync_query;
 pub mod join;
 pub mod lazy;
+pub mod lazy_owned;
 pub mod lazy_parallel;
 pub mod queryable;
 pub mod ext;
@@ -54,21 +58,31 @@ pub mod lock_lazy;
 pub mod lock_join;
 pub mod lock_view;
 pub mod lock_ext;
+#[doc(hidden)]
+pub mod column;
+#[cfg(feature = "hll")]
+mod hll;
 
 #[macro_use]
 pub mod macros;
 
-pub use query::{Query, QueryWithSkip};
-pub use join::JoinQuery;
-pub use lazy::LazyQuery;
+pub use query::{Query, QueryWithSkip, ComputedQuery, Page, NullsOrder, PercentileMethod, QueryStats, Describe, SyncFilter, flatten_groups_sorted, result_diff};
+#[cfg(feature = "serde")]
+pub use query::record_field;
+pub use plan::QueryPlan;
+pub use prepared::PreparedQuery;
+pub use sync_query::SyncQuery;
+pub use join::{JoinError, JoinIndex, JoinQuery, JoinQuery3};
+pub use lazy::{LazyQuery, interleave};
+pub use lazy_owned::LazyOwnedQuery;
 pub use lazy_parallel::{LazyParallelQuery, LazyParallelQueryExt};
 pub use queryable::Queryable;
-pub use ext::{QueryExt, QueryableExt};
+pub use ext::{QueryExt, QueryableExt, RangeQueryExt};
 pub use locks::{LockValue, LockQueryExt, LockIterExt, LockedValueRef};
 pub use lock_query::{LockQuery, LockQueryable, LockLazyQueryable};
 pub use lock_lazy::LockLazyQuery;
 pub use lock_join::{LockJoinQuery, LockJoinable, LockJoinableCollection};
-pub use lock_view::{LockView, MaterializedLockView};
+pub use lock_view::{LockView, MaterializedLockView, AsyncMaterializedView};
 
 // Re-export lock extensions for parking_lot and tokio
 #[cfg(feature = "parking_lot")]
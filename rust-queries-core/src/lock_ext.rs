@@ -7,12 +7,17 @@
 //!
 //! - **parking_lot Support**: High-performance RwLock and Mutex wrappers
 //! - **tokio Support**: Async RwLock support for async applications
-//! - **Extension Traits**: Direct `.lock_query()` and `.lock_join()` support
+//! - **Extension Traits**: Direct `.lock_join()` support
+//!
+//! The wrappers implement `LockValue`, so maps and vecs of them get
+//! `.lock_query()` and `.lock_lazy_query()` from the generic
+//! `LockQueryable`/`LockLazyQueryable` traits, like the std lock types.
 //!
 //! ## Example (parking_lot)
 //!
 //! ```ignore
-//! use rust_queries_core::lock_ext::{ParkingLotRwLockWrapper, ParkingLotQueryExt};
+//! use rust_queries_core::lock_ext::ParkingLotRwLockWrapper;
+//! use rust_queries_core::lock_query::LockQueryable;
 //! use std::collections::HashMap;
 //! use parking_lot::RwLock;
 //!
@@ -32,7 +37,8 @@
 //! ## Example (tokio)
 //!
 //! ```ignore
-//! use rust_queries_core::lock_ext::{TokioRwLockWrapper, TokioLockQueryExt};
+//! use rust_queries_core::lock_ext::TokioRwLockWrapper;
+//! use rust_queries_core::lock_query::LockQueryable;
 //! use std::collections::HashMap;
 //!
 //! async fn query_products(products: &HashMap<String, TokioRwLockWrapper<Product>>) {
@@ -46,12 +52,6 @@
 #[cfg(any(feature = "parking_lot", feature = "tokio"))]
 use crate::locks::LockValue;
 
-#[cfg(any(feature = "parking_lot", feature = "tokio"))]
-use crate::lock_query::LockQuery;
-
-#[cfg(any(feature = "parking_lot", feature = "tokio"))]
-use crate::lock_lazy::LockLazyQuery;
-
 #[cfg(any(feature = "parking_lot", feature = "tokio"))]
 use crate::lock_join::LockJoinQuery;
 
@@ -143,58 +143,21 @@ impl<T> LockValue<T> for ParkingLotMutexWrapper<T> {
 
 // Extension traits for parking_lot
 
-/// Extension trait to enable direct .lock_query() and .lock_lazy_query() calls
-/// on HashMap with parking_lot RwLock.
-#[cfg(feature = "parking_lot")]
-pub trait ParkingLotQueryExt<V> {
-    /// Create a LockQuery for SQL-like operations.
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotRwLockWrapper<V>>;
-    
-    /// Create a lazy lock query.
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotRwLockWrapper<V>, impl Iterator<Item = &ParkingLotRwLockWrapper<V>>>;
-}
-
-#[cfg(feature = "parking_lot")]
-impl<K, V: 'static> ParkingLotQueryExt<V> for HashMap<K, ParkingLotRwLockWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotRwLockWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotRwLockWrapper<V>, impl Iterator<Item = &ParkingLotRwLockWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-/// Extension trait for Mutex queries.
+/// Deprecated name for [`LockQueryable`](crate::lock_query::LockQueryable), which
+/// now covers maps of `ParkingLotRwLockWrapper`.
+///
+/// `.lock_lazy_query()` moved to [`LockLazyQueryable`](crate::lock_query::LockLazyQueryable).
 #[cfg(feature = "parking_lot")]
-pub trait ParkingLotMutexQueryExt<V> {
-    /// Create a LockQuery for SQL-like operations.
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotMutexWrapper<V>>;
-    
-    /// Create a lazy lock query.
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotMutexWrapper<V>, impl Iterator<Item = &ParkingLotMutexWrapper<V>>>;
-}
+#[deprecated(note = "use `LockQueryable`, and `LockLazyQueryable` for `lock_lazy_query`")]
+pub use crate::lock_query::LockQueryable as ParkingLotQueryExt;
 
+/// Deprecated name for [`LockQueryable`](crate::lock_query::LockQueryable), which
+/// now covers maps of `ParkingLotMutexWrapper`.
+///
+/// `.lock_lazy_query()` moved to [`LockLazyQueryable`](crate::lock_query::LockLazyQueryable).
 #[cfg(feature = "parking_lot")]
-impl<K, V: 'static> ParkingLotMutexQueryExt<V> for HashMap<K, ParkingLotMutexWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, ParkingLotMutexWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, ParkingLotMutexWrapper<V>, impl Iterator<Item = &ParkingLotMutexWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-// Extension trait for JOIN operations
+#[deprecated(note = "use `LockQueryable`, and `LockLazyQueryable` for `lock_lazy_query`")]
+pub use crate::lock_query::LockQueryable as ParkingLotMutexQueryExt;
 
 /// Extension trait for JOIN operations with parking_lot RwLock.
 #[cfg(feature = "parking_lot")]
@@ -329,58 +292,21 @@ impl<T> LockValue<T> for TokioMutexWrapper<T> {
 
 // Extension traits for tokio
 
-/// Extension trait to enable direct .lock_query() and .lock_lazy_query() calls
-/// on HashMap with tokio RwLock.
-#[cfg(feature = "tokio")]
-pub trait TokioLockQueryExt<V> {
-    /// Create a LockQuery for SQL-like operations.
-    fn lock_query(&self) -> LockQuery<'_, V, TokioRwLockWrapper<V>>;
-    
-    /// Create a lazy lock query.
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, TokioRwLockWrapper<V>, impl Iterator<Item = &TokioRwLockWrapper<V>>>;
-}
-
-#[cfg(feature = "tokio")]
-impl<K, V: 'static> TokioLockQueryExt<V> for HashMap<K, TokioRwLockWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, TokioRwLockWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, TokioRwLockWrapper<V>, impl Iterator<Item = &TokioRwLockWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-/// Extension trait for Mutex queries.
+/// Deprecated name for [`LockQueryable`](crate::lock_query::LockQueryable), which
+/// now covers maps of `TokioRwLockWrapper`.
+///
+/// `.lock_lazy_query()` moved to [`LockLazyQueryable`](crate::lock_query::LockLazyQueryable).
 #[cfg(feature = "tokio")]
-pub trait TokioMutexQueryExt<V> {
-    /// Create a LockQuery for SQL-like operations.
-    fn lock_query(&self) -> LockQuery<'_, V, TokioMutexWrapper<V>>;
-    
-    /// Create a lazy lock query.
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, TokioMutexWrapper<V>, impl Iterator<Item = &TokioMutexWrapper<V>>>;
-}
+#[deprecated(note = "use `LockQueryable`, and `LockLazyQueryable` for `lock_lazy_query`")]
+pub use crate::lock_query::LockQueryable as TokioLockQueryExt;
 
+/// Deprecated name for [`LockQueryable`](crate::lock_query::LockQueryable), which
+/// now covers maps of `TokioMutexWrapper`.
+///
+/// `.lock_lazy_query()` moved to [`LockLazyQueryable`](crate::lock_query::LockLazyQueryable).
 #[cfg(feature = "tokio")]
-impl<K, V: 'static> TokioMutexQueryExt<V> for HashMap<K, TokioMutexWrapper<V>>
-where
-    K: std::hash::Hash + Eq,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, TokioMutexWrapper<V>> {
-        let locks: Vec<_> = self.values().collect();
-        LockQuery::from_locks(locks)
-    }
-    
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, TokioMutexWrapper<V>, impl Iterator<Item = &TokioMutexWrapper<V>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-// Extension trait for JOIN operations
+#[deprecated(note = "use `LockQueryable`, and `LockLazyQueryable` for `lock_lazy_query`")]
+pub use crate::lock_query::LockQueryable as TokioMutexQueryExt;
 
 /// Extension trait for JOIN operations with tokio RwLock.
 #[cfg(feature = "tokio")]
@@ -453,5 +379,31 @@ mod tests {
         let result = wrapper.with_value(|v| *v * 2);
         assert_eq!(result, Some(84));
     }
-}
 
+    #[cfg(feature = "parking_lot")]
+    #[test]
+    fn test_wrapper_map_lock_query_with_glob_import() {
+        // Every re-exported trait in scope must leave exactly one `lock_query`.
+        use crate::*;
+
+        let mut map = HashMap::new();
+        map.insert("a", ParkingLotRwLockWrapper::new(1u32));
+        map.insert("b", ParkingLotRwLockWrapper::new(2u32));
+
+        assert_eq!(map.lock_query().count(), 2);
+        assert_eq!(map.lock_lazy_query().count(), 2);
+    }
+
+    #[cfg(feature = "parking_lot")]
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_query_ext_name_still_resolves() {
+        use crate::ParkingLotQueryExt;
+
+        let mut map = HashMap::new();
+        map.insert("a", ParkingLotRwLockWrapper::new(1u32));
+        map.insert("b", ParkingLotRwLockWrapper::new(2u32));
+
+        assert_eq!(map.lock_query().count(), 2);
+    }
+}
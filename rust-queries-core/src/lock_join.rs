@@ -91,6 +91,59 @@ where
         results
     }
 
+    /// Perform an INNER JOIN with an extra condition on each matched pair.
+    ///
+    /// Fuses `JOIN ... WHERE`: `predicate` is checked while the right lock is
+    /// held, and `mapper` only runs for pairs that pass it, so rejected pairs
+    /// are never built and right items are never cloned. This avoids
+    /// pre-filtering both sides into fresh locks before joining.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let big_orders = LockJoinQuery::new(&users, &orders)
+    ///     .inner_join_where(
+    ///         User::id(),
+    ///         Order::user_id(),
+    ///         |user, order| user.status == "active" && order.total > 100.0,
+    ///         |user, order| (user.name.clone(), order.total)
+    ///     );
+    /// ```
+    pub fn inner_join_where<LK, RK, P, M, Out>(
+        &self,
+        left_key: KeyPaths<L, LK>,
+        right_key: KeyPaths<R, RK>,
+        predicate: P,
+        mapper: M,
+    ) -> Vec<Out>
+    where
+        LK: Eq + Clone + 'static,
+        RK: Eq + Clone + 'static,
+        LK: PartialEq<RK>,
+        P: Fn(&L, &R) -> bool,
+        M: Fn(&L, &R) -> Out,
+        L: Clone,
+    {
+        let mut results = Vec::new();
+
+        for left_lock in &self.left {
+            let left_data = left_lock.with_value(|l| (left_key.get(l).cloned(), l.clone()));
+            if let Some((Some(left_k), left_item)) = left_data {
+                for right_lock in &self.right {
+                    let joined = right_lock.with_value(|r| {
+                        let matches = right_key.get(r).is_some_and(|right_k| left_k == *right_k);
+                        (matches && predicate(&left_item, r)).then(|| mapper(&left_item, r))
+                    });
+                    if let Some(Some(out)) = joined {
+                        results.push(out);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
     /// Perform a LEFT JOIN.
     ///
     /// Returns all left items with optional right matches.
@@ -331,5 +384,38 @@ mod tests {
 
         assert_eq!(results.len(), 3); // Alice (2 orders) + Bob (no orders)
     }
-}
 
+    #[test]
+    fn test_inner_join_where_matches_join_then_filter() {
+        use std::cell::Cell;
+
+        let (users, orders) = create_test_data();
+        let user_locks = || users.values().collect::<Vec<_>>();
+        let order_locks = || orders.values().collect::<Vec<_>>();
+
+        let mut expected: Vec<(String, u32)> = LockJoinQuery::new(user_locks(), order_locks())
+            .inner_join(User::id(), Order::user_id(), |user, order| (user.clone(), order.clone()))
+            .into_iter()
+            .filter(|(_, order)| order.total > 100.0)
+            .map(|(user, order)| (user.name, order.id))
+            .collect();
+
+        let built = Cell::new(0);
+        let mut fused = LockJoinQuery::new(user_locks(), order_locks()).inner_join_where(
+            User::id(),
+            Order::user_id(),
+            |_, order| order.total > 100.0,
+            |user, order| {
+                built.set(built.get() + 1);
+                (user.name.clone(), order.id)
+            },
+        );
+
+        expected.sort_by_key(|(_, id)| *id);
+        fused.sort_by_key(|(_, id)| *id);
+        assert_eq!(fused, expected);
+        assert_eq!(fused, vec![("Alice".to_string(), 102)]);
+        // Alice's 99.99 order matched the key but was rejected before mapping.
+        assert_eq!(built.get(), 1);
+    }
+}
@@ -6,6 +6,7 @@ use crate::locks::LockValue;
 use key_paths_core::KeyPaths;
 use std::marker::PhantomData;
 use std::collections::HashMap;
+use std::ops::ControlFlow;
 use std::time::SystemTime;
 
 #[cfg(feature = "datetime")]
@@ -544,6 +545,49 @@ where
         }).count()
     }
 
+    /// Fold items into an accumulator, stopping early on `ControlFlow::Break` (terminal).
+    ///
+    /// `f` runs while the item's lock is held. Once it returns `Break`, the
+    /// fold ends with that value and no further locks are acquired; if it never
+    /// breaks, the final `Continue` value is returned. Locks that cannot be
+    /// acquired (e.g. poisoned) are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::ops::ControlFlow;
+    ///
+    /// // Sum balances until the first negative one
+    /// let total = accounts
+    ///     .lock_lazy_query()
+    ///     .try_fold(0.0, |sum, account| {
+    ///         if account.balance < 0.0 {
+    ///             ControlFlow::Break(sum)
+    ///         } else {
+    ///             ControlFlow::Continue(sum + account.balance)
+    ///         }
+    ///     });
+    /// ```
+    pub fn try_fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &T) -> ControlFlow<B, B>,
+    {
+        // Held in an `Option` so the accumulator survives a lock that is skipped.
+        // The step is recorded by the closure itself, so a lock that runs the
+        // closure but still reports `None` cannot lose the accumulator.
+        let mut acc = Some(init);
+        for lock in self.iter {
+            let mut step = None;
+            lock.with_value(|item| step = acc.take().map(|acc| f(acc, item)));
+            match step {
+                Some(ControlFlow::Continue(next)) => acc = Some(next),
+                Some(ControlFlow::Break(done)) => return done,
+                None => {}
+            }
+        }
+        acc.expect("accumulator present")
+    }
+
     // ========================================================================
     // DATETIME OPERATIONS - SystemTime
     // ========================================================================
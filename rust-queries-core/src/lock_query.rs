@@ -23,8 +23,8 @@
 
 use crate::locks::LockValue;
 use key_paths_core::KeyPaths;
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock, Mutex};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 
 /// A query builder for locked data structures.
 ///
@@ -36,6 +36,8 @@ where
 {
     locks: Vec<&'a L>,
     filters: Vec<Box<dyn Fn(&T) -> bool + 'a>>,
+    max_locks: Option<usize>,
+    truncated: Cell<bool>,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -54,10 +56,53 @@ where
         Self {
             locks,
             filters: Vec::new(),
+            max_locks: None,
+            truncated: Cell::new(false),
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Cap how many locks each operation may acquire.
+    ///
+    /// Once the budget is exhausted the operation stops and returns what it has
+    /// collected so far; `was_truncated()` then reports `true`. This bounds
+    /// latency on large collections at the cost of completeness.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = LockQuery::new(&products).with_budget(100);
+    /// let results = query.all();
+    /// if query.was_truncated() {
+    ///     // results are partial
+    /// }
+    /// ```
+    pub fn with_budget(mut self, max_locks: usize) -> Self {
+        self.max_locks = Some(max_locks);
+        self
+    }
+
+    /// Check whether the last operation stopped early because the lock budget ran out.
+    ///
+    /// Always `false` when no budget is set.
+    pub fn was_truncated(&self) -> bool {
+        self.truncated.get()
+    }
+
+    /// Iterate the locks this operation may acquire, recording truncation.
+    fn budgeted_locks(&self) -> impl Iterator<Item = &'a L> + '_ {
+        self.truncated.set(false);
+        let budget = self.max_locks.unwrap_or(usize::MAX);
+        self.locks.iter().enumerate().map_while(move |(i, &lock)| {
+            if i < budget {
+                Some(lock)
+            } else {
+                self.truncated.set(true);
+                None
+            }
+        })
+    }
+
     /// Add a WHERE clause using a key-path.
     ///
     /// # Example
@@ -76,6 +121,30 @@ where
         self
     }
 
+    /// Add a WHERE ... IN (subquery) clause matching a field against a set of keys.
+    ///
+    /// The keys are collected into a `HashSet` once for O(1) membership checks.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let buyer_ids = orders
+    ///     .lock_query()
+    ///     .where_(Order::status(), |s| s == "completed")
+    ///     .select(Order::user_id());
+    /// let buyers = users.lock_query().where_in_subquery(User::id(), buyer_ids).all();
+    ///
+    /// // SQL equivalent:
+    /// // SELECT * FROM users WHERE id IN (SELECT user_id FROM orders WHERE status = 'completed')
+    /// ```
+    pub fn where_in_subquery<F>(self, path: KeyPaths<T, F>, keys: impl IntoIterator<Item = F>) -> Self
+    where
+        F: Eq + std::hash::Hash + 'static,
+    {
+        let keys: HashSet<F> = keys.into_iter().collect();
+        self.where_(path, move |value| keys.contains(value))
+    }
+
     /// Get all matching items (collects by cloning).
     ///
     /// # Example
@@ -87,8 +156,7 @@ where
     where
         T: Clone,
     {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -113,8 +181,7 @@ where
     where
         T: Clone,
     {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .find_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -135,8 +202,7 @@ where
     /// let count = query.count();
     /// ```
     pub fn count(&self) -> usize {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter(|lock| {
                 lock.with_value(|item| self.filters.iter().all(|f| f(item)))
                     .unwrap_or(false)
@@ -152,8 +218,7 @@ where
     /// let exists = query.exists();
     /// ```
     pub fn exists(&self) -> bool {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .any(|lock| {
                 lock.with_value(|item| self.filters.iter().all(|f| f(item)))
                     .unwrap_or(false)
@@ -171,8 +236,7 @@ where
     where
         T: Clone,
     {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -187,6 +251,114 @@ where
             .collect()
     }
 
+    /// Skip the first `offset` matching items and return up to `limit` of the rest.
+    ///
+    /// Locks are acquired one at a time and iteration stops as soon as
+    /// `offset + limit` matches have been seen, so the remainder of the
+    /// collection is never locked. Skipped items are not cloned.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Page 3 with 20 items per page
+    /// let page = query.limit_offset(40, 20);
+    ///
+    /// // SQL equivalent: SELECT * FROM products LIMIT 20 OFFSET 40
+    /// ```
+    pub fn limit_offset(&self, offset: usize, limit: usize) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut results = Vec::new();
+        if limit == 0 {
+            return results;
+        }
+
+        let mut to_skip = offset;
+        for lock in self.budgeted_locks() {
+            let item = lock
+                .with_value(|item| {
+                    if !self.filters.iter().all(|f| f(item)) {
+                        None
+                    } else if to_skip > 0 {
+                        to_skip -= 1;
+                        None
+                    } else {
+                        Some(item.clone())
+                    }
+                })
+                .flatten();
+
+            if let Some(item) = item {
+                results.push(item);
+                if results.len() == limit {
+                    break;
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Filter, sort and page in one call, cloning only the returned window.
+    ///
+    /// The ordering is worked out over clones of the sort field alone; full
+    /// items are cloned only for the `limit` results starting at `offset`.
+    /// Ties keep collection order. Items without a sort value sort first in
+    /// ascending order and last in descending order. Each returned lock is
+    /// acquired a second time to clone its item, so an item updated between
+    /// the two passes is returned with its newer value.
+    ///
+    /// # Arguments
+    ///
+    /// * `sort` - The key-path to the field to sort by
+    /// * `desc` - Whether to sort in descending order
+    /// * `offset` - The number of sorted matches to skip
+    /// * `limit` - The maximum number of items to return
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let page = products.lock_query()
+    ///     .where_(Product::category(), |cat| cat == "Electronics")
+    ///     .list(Product::name(), false, 20, 10);
+    ///
+    /// // SQL equivalent: SELECT * FROM products WHERE category = 'Electronics'
+    /// //                 ORDER BY name LIMIT 10 OFFSET 20
+    /// ```
+    pub fn list<F>(&self, sort: KeyPaths<T, F>, desc: bool, offset: usize, limit: usize) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+        T: Clone,
+    {
+        let mut keyed: Vec<(&'a L, Option<F>)> = self
+            .budgeted_locks()
+            .filter_map(|lock| {
+                lock.with_value(|item| {
+                    if self.filters.iter().all(|f| f(item)) {
+                        Some((lock, sort.get(item).cloned()))
+                    } else {
+                        None
+                    }
+                })
+                .flatten()
+            })
+            .collect();
+
+        if desc {
+            keyed.sort_by(|a, b| b.1.cmp(&a.1));
+        } else {
+            keyed.sort_by(|a, b| a.1.cmp(&b.1));
+        }
+
+        keyed
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|(lock, _)| lock.with_value(|item| item.clone()))
+            .collect()
+    }
+
     /// Select/project a field.
     ///
     /// # Example
@@ -198,8 +370,7 @@ where
     where
         F: Clone + 'static,
     {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -224,8 +395,7 @@ where
     where
         F: Clone + std::ops::Add<Output = F> + Default + 'static,
     {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -371,7 +541,7 @@ where
     {
         let mut groups: HashMap<F, Vec<T>> = HashMap::new();
 
-        for lock in &self.locks {
+        for lock in self.budgeted_locks() {
             if let Some(item) = lock.with_value(|item| {
                 if self.filters.iter().all(|f| f(item)) {
                     Some(item.clone())
@@ -390,6 +560,81 @@ where
         groups
     }
 
+    /// Count matching items per group.
+    ///
+    /// Each lock is acquired once and only the group key is cloned.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let per_category = query.count_by(Product::category());
+    ///
+    /// // SQL equivalent: SELECT category, COUNT(*) FROM products GROUP BY category
+    /// ```
+    pub fn count_by<K>(&self, key: KeyPaths<T, K>) -> HashMap<K, usize>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut counts: HashMap<K, usize> = HashMap::new();
+
+        for lock in self.budgeted_locks() {
+            let group = lock
+                .with_value(|item| {
+                    if self.filters.iter().all(|f| f(item)) {
+                        key.get(item).cloned()
+                    } else {
+                        None
+                    }
+                })
+                .flatten();
+
+            if let Some(group) = group {
+                *counts.entry(group).or_insert(0) += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Sum a numeric field per group.
+    ///
+    /// Each lock is acquired once and only the group key and value are cloned.
+    /// Items missing the value field are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let revenue = query.sum_by_group(Product::category(), Product::price());
+    ///
+    /// // SQL equivalent: SELECT category, SUM(price) FROM products GROUP BY category
+    /// ```
+    pub fn sum_by_group<K, F>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, F>) -> HashMap<K, F>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: Clone + std::ops::Add<Output = F> + Default + 'static,
+    {
+        let mut sums: HashMap<K, F> = HashMap::new();
+
+        for lock in self.budgeted_locks() {
+            let entry = lock
+                .with_value(|item| {
+                    if self.filters.iter().all(|f| f(item)) {
+                        Some((key.get(item)?.clone(), value.get(item)?.clone()))
+                    } else {
+                        None
+                    }
+                })
+                .flatten();
+
+            if let Some((group, value)) = entry {
+                let sum = sums.entry(group).or_default();
+                *sum = std::mem::take(sum) + value;
+            }
+        }
+
+        sums
+    }
+
     // i64 DateTime Aggregators (Unix timestamps in milliseconds)
     /// Finds minimum i64 timestamp value.
     ///
@@ -399,8 +644,7 @@ where
     /// let earliest = query.min_timestamp(Event::created_at());
     /// ```
     pub fn min_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -422,8 +666,7 @@ where
     /// let latest = query.max_timestamp(Event::created_at());
     /// ```
     pub fn max_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -445,8 +688,7 @@ where
     /// let avg = query.avg_timestamp(Event::created_at());
     /// ```
     pub fn avg_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        let items: Vec<i64> = self.locks
-            .iter()
+        let items: Vec<i64> = self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -474,8 +716,7 @@ where
     /// let total = query.sum_timestamp(Event::created_at());
     /// ```
     pub fn sum_timestamp(&self, path: KeyPaths<T, i64>) -> i64 {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter_map(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -497,8 +738,7 @@ where
     /// let count = query.count_timestamp(Event::created_at());
     /// ```
     pub fn count_timestamp(&self, path: KeyPaths<T, i64>) -> usize {
-        self.locks
-            .iter()
+        self.budgeted_locks()
             .filter(|lock| {
                 lock.with_value(|item| {
                     if self.filters.iter().all(|f| f(item)) {
@@ -671,45 +911,54 @@ where
 }
 
 
-/// Helper to create LockQuery from HashMap.
+/// Helper to create LockQuery from a collection of locks.
+///
+/// Implemented for every `HashMap<K, L>` and `Vec<L>` whose lock type `L`
+/// implements [`LockValue`], so custom lock types only need a `LockValue`
+/// impl to get `.lock_query()`.
 pub trait LockQueryable<T, L>
 where
     L: LockValue<T>,
 {
     /// Create a LockQuery for SQL-like operations.
+    ///
+    /// The query collects references to every lock up front so it can be run
+    /// repeatedly. For a single early-terminating pass over a large map, such
+    /// as `first()` or `any()`, prefer `LockLazyQueryable::lock_lazy_query`,
+    /// which iterates the map directly and stops at the first match.
     fn lock_query(&self) -> LockQuery<'_, T, L>;
-}
 
-// Implementation for HashMap<K, Arc<RwLock<V>>>
-impl<K, V> LockQueryable<V, Arc<RwLock<V>>> for HashMap<K, Arc<RwLock<V>>>
-where
-    K: Eq + std::hash::Hash,
-{
-    fn lock_query(&self) -> LockQuery<'_, V, Arc<RwLock<V>>> {
-        LockQuery::from_locks(self.values().collect())
+    /// Create a LockQuery that acquires at most `max_locks` locks per operation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = products.lock_query_budget(50);
+    /// let page = query.all();
+    /// let partial = query.was_truncated();
+    /// ```
+    fn lock_query_budget(&self, max_locks: usize) -> LockQuery<'_, T, L> {
+        self.lock_query().with_budget(max_locks)
     }
 }
 
-// Implementation for HashMap<K, Arc<Mutex<V>>>
-impl<K, V> LockQueryable<V, Arc<Mutex<V>>> for HashMap<K, Arc<Mutex<V>>>
+// Implementation for HashMap<K, L> over any lock type
+impl<K, T: 'static, L> LockQueryable<T, L> for HashMap<K, L>
 where
     K: Eq + std::hash::Hash,
+    L: LockValue<T>,
 {
-    fn lock_query(&self) -> LockQuery<'_, V, Arc<Mutex<V>>> {
+    fn lock_query(&self) -> LockQuery<'_, T, L> {
         LockQuery::from_locks(self.values().collect())
     }
 }
 
-// Implementation for Vec<Arc<RwLock<T>>>
-impl<T> LockQueryable<T, Arc<RwLock<T>>> for Vec<Arc<RwLock<T>>> {
-    fn lock_query(&self) -> LockQuery<'_, T, Arc<RwLock<T>>> {
-        LockQuery::from_locks(self.iter().collect())
-    }
-}
-
-// Implementation for Vec<Arc<Mutex<T>>>
-impl<T> LockQueryable<T, Arc<Mutex<T>>> for Vec<Arc<Mutex<T>>> {
-    fn lock_query(&self) -> LockQuery<'_, T, Arc<Mutex<T>>> {
+// Implementation for Vec<L> over any lock type
+impl<T: 'static, L> LockQueryable<T, L> for Vec<L>
+where
+    L: LockValue<T>,
+{
+    fn lock_query(&self) -> LockQuery<'_, T, L> {
         LockQuery::from_locks(self.iter().collect())
     }
 }
@@ -718,44 +967,38 @@ impl<T> LockQueryable<T, Arc<Mutex<T>>> for Vec<Arc<Mutex<T>>> {
 use crate::lock_lazy::LockLazyQuery;
 
 /// Extension trait for creating lazy lock queries.
+///
+/// Like [`LockQueryable`], implemented for every `HashMap<K, L>` and `Vec<L>`
+/// whose lock type implements [`LockValue`].
 pub trait LockLazyQueryable<T, L>
 where
     L: LockValue<T>,
 {
     /// Create a lazy lock query.
+    ///
+    /// Borrows the collection's iterator directly: no lock references are
+    /// collected, and early-terminating operations only acquire the locks
+    /// they reach.
     fn lock_lazy_query(&self) -> LockLazyQuery<'_, T, L, impl Iterator<Item = &L>>;
 }
 
-// Implementation for HashMap<K, Arc<RwLock<V>>>
-impl<K, V> LockLazyQueryable<V, Arc<RwLock<V>>> for HashMap<K, Arc<RwLock<V>>>
+// Implementation for HashMap<K, L> over any lock type
+impl<K, T: 'static, L> LockLazyQueryable<T, L> for HashMap<K, L>
 where
     K: Eq + std::hash::Hash,
+    L: LockValue<T>,
 {
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, Arc<RwLock<V>>, impl Iterator<Item = &Arc<RwLock<V>>>> {
+    fn lock_lazy_query(&self) -> LockLazyQuery<'_, T, L, impl Iterator<Item = &L>> {
         LockLazyQuery::new(self.values())
     }
 }
 
-// Implementation for HashMap<K, Arc<Mutex<V>>>
-impl<K, V> LockLazyQueryable<V, Arc<Mutex<V>>> for HashMap<K, Arc<Mutex<V>>>
+// Implementation for Vec<L> over any lock type
+impl<T: 'static, L> LockLazyQueryable<T, L> for Vec<L>
 where
-    K: Eq + std::hash::Hash,
+    L: LockValue<T>,
 {
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, V, Arc<Mutex<V>>, impl Iterator<Item = &Arc<Mutex<V>>>> {
-        LockLazyQuery::new(self.values())
-    }
-}
-
-// Implementation for Vec<Arc<RwLock<T>>>
-impl<T> LockLazyQueryable<T, Arc<RwLock<T>>> for Vec<Arc<RwLock<T>>> {
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, T, Arc<RwLock<T>>, impl Iterator<Item = &Arc<RwLock<T>>>> {
-        LockLazyQuery::new(self.iter())
-    }
-}
-
-// Implementation for Vec<Arc<Mutex<T>>>
-impl<T> LockLazyQueryable<T, Arc<Mutex<T>>> for Vec<Arc<Mutex<T>>> {
-    fn lock_lazy_query(&self) -> LockLazyQuery<'_, T, Arc<Mutex<T>>, impl Iterator<Item = &Arc<Mutex<T>>>> {
+    fn lock_lazy_query(&self) -> LockLazyQuery<'_, T, L, impl Iterator<Item = &L>> {
         LockLazyQuery::new(self.iter())
     }
 }
@@ -853,5 +1096,294 @@ mod tests {
         assert_eq!(sorted[0].price, 29.99);
         assert_eq!(sorted[2].price, 999.99);
     }
-}
 
+    #[test]
+    fn test_lock_query_count_by_and_sum_by_group() {
+        let map = create_test_map();
+        let snapshot = map.lock_query().all();
+        let expected = crate::Query::new(&snapshot).group_by(Product::category());
+
+        let counts = map.lock_query().count_by(Product::category());
+        assert_eq!(counts.len(), expected.len());
+        for (category, items) in &expected {
+            assert_eq!(counts[category], items.len());
+        }
+
+        let sums = map.lock_query().sum_by_group(Product::category(), Product::price());
+        for (category, items) in &expected {
+            let total: f64 = crate::Query::new(items).sum(Product::price());
+            assert!((sums[category] - total).abs() < 1e-9);
+        }
+
+        let filtered = map
+            .lock_query()
+            .where_(Product::price(), |&p| p > 100.0)
+            .count_by(Product::category());
+        assert_eq!(filtered.get("Electronics"), Some(&1));
+        assert_eq!(filtered.get("Furniture"), Some(&1));
+    }
+
+    thread_local! {
+        static TRACKED_CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    #[derive(Keypath)]
+    struct Tracked {
+        id: u32,
+        name: String,
+    }
+
+    impl Clone for Tracked {
+        fn clone(&self) -> Self {
+            TRACKED_CLONES.with(|c| c.set(c.get() + 1));
+            Tracked { id: self.id, name: self.name.clone() }
+        }
+    }
+
+    #[test]
+    fn test_lock_query_list_clones_only_window() {
+        let names = ["delta", "alpha", "echo", "charlie", "bravo", "foxtrot"];
+        let items: Vec<Arc<RwLock<Tracked>>> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Arc::new(RwLock::new(Tracked { id: i as u32, name: name.to_string() })))
+            .collect();
+
+        TRACKED_CLONES.with(|c| c.set(0));
+        let page = items
+            .lock_query()
+            .where_(Tracked::id(), |&id| id != 2)
+            .list(Tracked::name(), false, 1, 2);
+        assert_eq!(TRACKED_CLONES.with(|c| c.get()), 2);
+        let page_names: Vec<&str> = page.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(page_names, vec!["bravo", "charlie"]);
+
+        TRACKED_CLONES.with(|c| c.set(0));
+        let top = items.lock_query().list(Tracked::name(), true, 0, 3);
+        assert_eq!(TRACKED_CLONES.with(|c| c.get()), 3);
+        let top_names: Vec<&str> = top.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(top_names, vec!["foxtrot", "echo", "delta"]);
+
+        assert!(items.lock_query().list(Tracked::name(), false, 10, 5).is_empty());
+    }
+
+    /// Lock wrapper that records how many times it has been acquired.
+    struct CountingLock<'c> {
+        value: RwLock<u32>,
+        acquisitions: &'c std::sync::atomic::AtomicUsize,
+    }
+
+    impl LockValue<u32> for CountingLock<'_> {
+        fn with_value<F, R>(&self, f: F) -> Option<R>
+        where
+            F: FnOnce(&u32) -> R,
+        {
+            self.acquisitions
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.value.with_value(f)
+        }
+    }
+
+    #[test]
+    fn test_lock_query_limit_offset() {
+        let map = create_test_map();
+        let mut locks: Vec<_> = map.iter().collect();
+        locks.sort_by_key(|(key, _)| key.as_str());
+        let query = LockQuery::from_locks(locks.into_iter().map(|(_, lock)| lock).collect());
+
+        let page = query.limit_offset(1, 1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, 2);
+
+        let rest = query.limit_offset(1, 10);
+        assert_eq!(rest.iter().map(|p| p.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        assert!(query.limit_offset(3, 10).is_empty());
+        assert!(query.limit_offset(0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_lock_query_limit_offset_stops_early() {
+        let acquisitions = std::sync::atomic::AtomicUsize::new(0);
+        let locks: Vec<CountingLock> = (0..1000)
+            .map(|i| CountingLock {
+                value: RwLock::new(i),
+                acquisitions: &acquisitions,
+            })
+            .collect();
+
+        let page = LockQuery::from_locks(locks.iter().collect()).limit_offset(10, 5);
+        assert_eq!(page, vec![10, 11, 12, 13, 14]);
+        assert_eq!(acquisitions.load(std::sync::atomic::Ordering::SeqCst), 15);
+
+        acquisitions.store(0, std::sync::atomic::Ordering::SeqCst);
+        let evens = LockQuery::from_locks(locks.iter().collect())
+            .where_(KeyPaths::readable(|v: &u32| v), |v| v % 2 == 0)
+            .limit_offset(2, 3);
+        assert_eq!(evens, vec![4, 6, 8]);
+        assert_eq!(acquisitions.load(std::sync::atomic::Ordering::SeqCst), 9);
+    }
+
+    #[test]
+    fn test_lock_query_budget_truncates() {
+        let acquisitions = std::sync::atomic::AtomicUsize::new(0);
+        let locks: Vec<CountingLock> = (0..100)
+            .map(|i| CountingLock {
+                value: RwLock::new(i),
+                acquisitions: &acquisitions,
+            })
+            .collect();
+
+        let query = LockQuery::from_locks(locks.iter().collect()).with_budget(10);
+        let results = query.all();
+        assert!(results.len() <= 10);
+        assert_eq!(results, (0..10).collect::<Vec<_>>());
+        assert!(query.was_truncated());
+        assert_eq!(acquisitions.load(std::sync::atomic::Ordering::SeqCst), 10);
+
+        // Finishing within budget clears the flag.
+        assert_eq!(query.first(), Some(0));
+        assert!(!query.was_truncated());
+
+        let map = create_test_map();
+        let small = map.lock_query_budget(2);
+        assert_eq!(small.count(), 2);
+        assert!(small.was_truncated());
+
+        let large = map.lock_query_budget(3);
+        assert_eq!(large.count(), 3);
+        assert!(!large.was_truncated());
+    }
+
+    /// Minimal custom lock: a `RefCell` that reports borrowed values as unavailable.
+    struct CellLock<T>(std::cell::RefCell<T>);
+
+    impl<T> LockValue<T> for CellLock<T> {
+        fn with_value<F, R>(&self, f: F) -> Option<R>
+        where
+            F: FnOnce(&T) -> R,
+        {
+            self.0.try_borrow().ok().map(|value| f(&value))
+        }
+    }
+
+    #[test]
+    fn test_custom_lock_type_is_queryable() {
+        let mut map: HashMap<&str, CellLock<Product>> = HashMap::new();
+        for (key, id, price, category) in [("a", 1, 10.0, "Books"), ("b", 2, 250.0, "Tools"), ("c", 3, 40.0, "Books")] {
+            map.insert(
+                key,
+                CellLock(std::cell::RefCell::new(Product {
+                    id,
+                    name: key.to_string(),
+                    price,
+                    category: category.to_string(),
+                })),
+            );
+        }
+
+        let books = map.lock_query().where_(Product::category(), |c| c == "Books").count();
+        assert_eq!(books, 2);
+
+        let expensive: Vec<u32> = map
+            .lock_lazy_query()
+            .where_(Product::price(), |&p| p > 100.0)
+            .select_lazy(Product::id())
+            .collect();
+        assert_eq!(expensive, vec![2]);
+
+        // A value that is currently borrowed mutably is skipped.
+        let _guard = map["b"].0.borrow_mut();
+        assert_eq!(map.lock_query().count(), 2);
+
+        let list = vec![CellLock(std::cell::RefCell::new(3u8))];
+        assert_eq!(LockQueryable::<u8, _>::lock_query(&list).count(), 1);
+    }
+
+    #[test]
+    fn test_lock_lazy_query_on_map_acquires_only_needed_locks() {
+        let acquisitions = std::sync::atomic::AtomicUsize::new(0);
+        let map: HashMap<u32, CountingLock> = (0..10_000)
+            .map(|i| (i, CountingLock { value: RwLock::new(i), acquisitions: &acquisitions }))
+            .collect();
+        let value = || KeyPaths::readable(|v: &u32| v);
+        let acquired = || acquisitions.swap(0, std::sync::atomic::Ordering::SeqCst);
+
+        assert!(map.lock_lazy_query().first().is_some());
+        assert_eq!(acquired(), 1);
+
+        assert!(map.lock_lazy_query().where_(value(), |_| true).any());
+        assert_eq!(acquired(), 1);
+
+        // The first value divisible by 7 is reached well before the end of the map.
+        let found = map.lock_lazy_query().where_(value(), |v| v % 7 == 0).first();
+        let touched = acquired();
+        assert!(found.is_some_and(|v| v % 7 == 0));
+        assert!(touched < map.len() / 10, "acquired {} locks", touched);
+
+        // Filtering without early termination visits every lock.
+        assert_eq!(map.lock_lazy_query().where_(value(), |v| v % 7 == 0).count(), 1429);
+        assert_eq!(acquired(), map.len());
+    }
+
+    #[test]
+    fn test_lock_lazy_try_fold_stops_acquiring_after_break() {
+        use std::ops::ControlFlow;
+
+        let acquisitions = std::sync::atomic::AtomicUsize::new(0);
+        let balances = [5, 10, 0, 7, 8, 9];
+        let locks: Vec<CountingLock> = balances
+            .iter()
+            .map(|&b| CountingLock { value: RwLock::new(b), acquisitions: &acquisitions })
+            .collect();
+
+        // Sum until the first empty balance.
+        let total = locks.lock_lazy_query().try_fold(0, |sum, &balance| {
+            if balance == 0 {
+                ControlFlow::Break(sum)
+            } else {
+                ControlFlow::Continue(sum + balance)
+            }
+        });
+        assert_eq!(total, 15);
+        assert_eq!(acquisitions.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        acquisitions.store(0, std::sync::atomic::Ordering::SeqCst);
+        let total = locks.lock_lazy_query().try_fold(0, |sum, &b| ControlFlow::Continue(sum + b));
+        assert_eq!(total, 39);
+        assert_eq!(acquisitions.load(std::sync::atomic::Ordering::SeqCst), balances.len());
+    }
+
+    /// Lock that runs the closure but then reports the value as unavailable.
+    struct ForgetfulLock(RwLock<u32>);
+
+    impl LockValue<u32> for ForgetfulLock {
+        fn with_value<F, R>(&self, f: F) -> Option<R>
+        where
+            F: FnOnce(&u32) -> R,
+        {
+            self.0.with_value(f);
+            None
+        }
+    }
+
+    #[test]
+    fn test_lock_lazy_try_fold_keeps_accumulator_when_lock_reports_none() {
+        use std::ops::ControlFlow;
+
+        let locks: Vec<ForgetfulLock> = [1, 2, 3].iter().map(|&v| ForgetfulLock(RwLock::new(v))).collect();
+        let total = locks.lock_lazy_query().try_fold(0, |sum, &v| ControlFlow::Continue(sum + v));
+        assert_eq!(total, 6);
+
+        let poisoned = Arc::new(RwLock::new(10u32));
+        let writer = poisoned.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = writer.write().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        let locks = vec![Arc::new(RwLock::new(1u32)), poisoned, Arc::new(RwLock::new(2u32))];
+        let total = locks.lock_lazy_query().try_fold(0, |sum, &v| ControlFlow::Continue(sum + v));
+        assert_eq!(total, 3);
+    }
+}
@@ -22,7 +22,11 @@
 
 use crate::lock_query::LockQuery;
 use crate::locks::LockValue;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
 /// A reusable query pattern (like a SQL VIEW).
 ///
@@ -121,6 +125,94 @@ where
     }
 }
 
+type RefreshFuture<T> = Pin<Box<dyn Future<Output = Vec<T>> + Send>>;
+
+/// Materialized view refreshed by an async closure.
+///
+/// Useful when the source sits behind async locks such as `tokio::sync::RwLock`:
+/// `refresh()` awaits the locks, while `get()` and `count()` read the cached
+/// snapshot synchronously without waiting.
+///
+/// Refreshing is cancellation-safe. The snapshot is only replaced once the
+/// refresh closure completes, so dropping an in-flight `refresh()` leaves the
+/// previous snapshot in place. If several refreshes overlap, the most recently
+/// started one wins: a refresh that finishes after a newer one is discarded.
+pub struct AsyncMaterializedView<T> {
+    snapshot: RwLock<(u64, Arc<Vec<T>>)>,
+    generation: AtomicU64,
+    refresh_fn: Box<dyn Fn() -> RefreshFuture<T> + Send + Sync>,
+}
+
+impl<T> AsyncMaterializedView<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Create a new async materialized view, awaiting the first refresh.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let view = AsyncMaterializedView::new(move || {
+    ///     let products = products.clone();
+    ///     async move {
+    ///         let mut active = Vec::new();
+    ///         for lock in products.values() {
+    ///             let product = lock.read().await;
+    ///             if product.active {
+    ///                 active.push(product.clone());
+    ///             }
+    ///         }
+    ///         active
+    ///     }
+    /// })
+    /// .await;
+    /// ```
+    pub async fn new<F, Fut>(refresh_fn: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Vec<T>> + Send + 'static,
+    {
+        let refresh_fn: Box<dyn Fn() -> RefreshFuture<T> + Send + Sync> =
+            Box::new(move || Box::pin(refresh_fn()));
+        let data = refresh_fn().await;
+        Self {
+            snapshot: RwLock::new((0, Arc::new(data))),
+            generation: AtomicU64::new(0),
+            refresh_fn,
+        }
+    }
+
+    /// Get the last successfully refreshed snapshot.
+    pub fn get(&self) -> Arc<Vec<T>> {
+        self.snapshot
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .1
+            .clone()
+    }
+
+    /// Refresh the view with latest data.
+    ///
+    /// The snapshot is left unchanged if a refresh started after this one has
+    /// already been stored.
+    pub async fn refresh(&self) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let data = (self.refresh_fn)().await;
+        let mut snapshot = self
+            .snapshot
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if snapshot.0 < generation {
+            *snapshot = (generation, Arc::new(data));
+        }
+    }
+
+    /// Get count without refreshing.
+    pub fn count(&self) -> usize {
+        self.get().len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,5 +250,93 @@ mod tests {
         assert_eq!(mat_view.count(), 1);
         assert_eq!(mat_view.get()[0].name, "A");
     }
-}
 
+    /// Polls a future once with a no-op waker.
+    #[cfg(feature = "tokio")]
+    fn poll_once<F: Future + ?Sized>(future: Pin<&mut F>) -> std::task::Poll<F::Output> {
+        struct NoopWake;
+        impl std::task::Wake for NoopWake {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = std::task::Waker::from(Arc::new(NoopWake));
+        future.poll(&mut std::task::Context::from_waker(&waker))
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_async_materialized_view_keeps_snapshot_during_refresh() {
+        use std::task::Poll;
+
+        let source = Arc::new(tokio::sync::RwLock::new(vec![1u32, 2, 3]));
+        let reader = source.clone();
+        let mut init = Box::pin(AsyncMaterializedView::new(move || {
+            let reader = reader.clone();
+            async move { reader.read().await.iter().copied().filter(|v| v % 2 == 1).collect() }
+        }));
+        let view = match poll_once(init.as_mut()) {
+            Poll::Ready(view) => view,
+            Poll::Pending => panic!("initial refresh should not block"),
+        };
+        assert_eq!(*view.get(), vec![1, 3]);
+
+        // Hold the source write lock so the refresh stays in flight.
+        let mut guard = source.try_write().unwrap();
+        guard.extend([5, 7]);
+        let mut refresh = Box::pin(view.refresh());
+        assert!(poll_once(refresh.as_mut()).is_pending());
+        assert_eq!(*view.get(), vec![1, 3]);
+        assert_eq!(view.count(), 2);
+
+        // Cancelling the in-flight refresh keeps the previous snapshot.
+        drop(refresh);
+        drop(guard);
+        assert_eq!(*view.get(), vec![1, 3]);
+
+        let mut refresh = Box::pin(view.refresh());
+        assert!(poll_once(refresh.as_mut()).is_ready());
+        assert_eq!(*view.get(), vec![1, 3, 5, 7]);
+        assert_eq!(view.count(), 4);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_async_materialized_view_ignores_stale_refresh() {
+        use std::collections::VecDeque;
+        use std::sync::Mutex;
+        use tokio::sync::oneshot;
+
+        let pending: Arc<Mutex<VecDeque<oneshot::Receiver<Vec<u32>>>>> = Arc::default();
+        let mut senders = Vec::new();
+        for _ in 0..3 {
+            let (tx, rx) = oneshot::channel();
+            pending.lock().unwrap().push_back(rx);
+            senders.push(tx);
+        }
+        let queue = pending.clone();
+        let mut init = Box::pin(AsyncMaterializedView::new(move || {
+            let rx = queue.lock().unwrap().pop_front().unwrap();
+            async move { rx.await.unwrap() }
+        }));
+        let mut senders = senders.into_iter();
+        senders.next().unwrap().send(vec![1]).unwrap();
+        let view = match poll_once(init.as_mut()) {
+            std::task::Poll::Ready(view) => view,
+            std::task::Poll::Pending => panic!("initial refresh should be ready"),
+        };
+
+        // Start an older and a newer refresh; the newer one finishes first.
+        let mut older = Box::pin(view.refresh());
+        assert!(poll_once(older.as_mut()).is_pending());
+        let mut newer = Box::pin(view.refresh());
+        assert!(poll_once(newer.as_mut()).is_pending());
+
+        let (older_tx, newer_tx) = (senders.next().unwrap(), senders.next().unwrap());
+        newer_tx.send(vec![1, 2, 3]).unwrap();
+        assert!(poll_once(newer.as_mut()).is_ready());
+        assert_eq!(*view.get(), vec![1, 2, 3]);
+
+        older_tx.send(vec![1, 2]).unwrap();
+        assert!(poll_once(older.as_mut()).is_ready());
+        assert_eq!(*view.get(), vec![1, 2, 3]);
+    }
+}
@@ -33,7 +33,32 @@ use std::collections::HashMap;
 
 /// Helper trait for lock-aware value extraction.
 ///
-/// This trait enables querying locked data without cloning.
+/// This trait enables querying locked data without cloning. It is the single
+/// integration point for lock types: implementing it for a custom lock (a
+/// sharded lock, or a lock from another crate behind a newtype) makes
+/// `HashMap<K, L>` and `Vec<L>` of that lock queryable through
+/// `LockQueryable::lock_query` and `LockLazyQueryable::lock_lazy_query`.
+///
+/// Return `None` when the value cannot be accessed, e.g. a poisoned lock;
+/// such items are skipped by queries.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Shared<T>(Arc<parking_lot::RwLock<T>>);
+///
+/// impl<T> LockValue<T> for Shared<T> {
+///     fn with_value<F, R>(&self, f: F) -> Option<R>
+///     where
+///         F: FnOnce(&T) -> R,
+///     {
+///         Some(f(&self.0.read()))
+///     }
+/// }
+///
+/// let map: HashMap<u32, Shared<Product>> = /* ... */;
+/// let cheap = map.lock_query().where_(Product::price(), |&p| p < 10.0).all();
+/// ```
 pub trait LockValue<T> {
     /// Execute a function with access to the inner value.
     fn with_value<F, R>(&self, f: F) -> Option<R>
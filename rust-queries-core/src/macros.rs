@@ -257,3 +257,21 @@ macro_rules! select_where {
     }};
 }
 
+/// Projects query results into JSON objects with the named fields.
+///
+/// Shorthand for `Query::select_records` with each key-path wrapped in
+/// `record_field`. Requires the `serde` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// let rows = select_records!(query, "id" => Product::id(), "name" => Product::name());
+/// // [{"id": 1, "name": "Laptop"}, ...]
+/// ```
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! select_records {
+    ($query:expr, $($name:expr => $path:expr),+ $(,)?) => {{
+        $query.select_records(&[$(($name, $crate::record_field($path))),+])
+    }};
+}
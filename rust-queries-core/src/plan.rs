@@ -0,0 +1,239 @@
+//! Cached filter masks for running many queries over the same slice.
+//!
+//! This module provides the `QueryPlan` struct which evaluates filters once into
+//! a boolean mask and combines masks with AND, OR, and NOT before collecting.
+
+use crate::query::Query;
+
+/// A boolean mask over a slice, combinable with other masks.
+///
+/// Evaluating a stable predicate (like "is active") once and reusing its mask
+/// avoids recomputing it for every composite query.
+///
+/// # Example
+///
+/// ```ignore
+/// let active = Query::new(&users).where_(User::active(), |&a| a).mask();
+/// let admins = Query::new(&users).where_(User::role(), |r| r == "admin").mask();
+///
+/// let inactive_admins = QueryPlan::from_mask(&users, active)
+///     .not()
+///     .and(&admins)
+///     .all();
+/// ```
+pub struct QueryPlan<'a, T: 'static> {
+    data: &'a [T],
+    mask: Vec<bool>,
+}
+
+impl<'a, T: 'static> QueryPlan<'a, T> {
+    /// Creates a plan matching every item in the slice.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let plan = QueryPlan::new(&products);
+    /// ```
+    pub fn new(data: &'a [T]) -> Self {
+        Self {
+            data,
+            mask: vec![true; data.len()],
+        }
+    }
+
+    /// Creates a plan from a query by evaluating its filters once.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let plan = QueryPlan::from_query(&Query::new(&products).where_(Product::stock(), |&s| s > 0));
+    /// ```
+    pub fn from_query(query: &Query<'a, T>) -> Self {
+        Self {
+            data: query.data,
+            mask: query.mask(),
+        }
+    }
+
+    /// Creates a plan from a previously computed mask.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask.len()` differs from `data.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let plan = QueryPlan::from_mask(&products, cached_mask.clone());
+    /// ```
+    pub fn from_mask(data: &'a [T], mask: Vec<bool>) -> Self {
+        assert_eq!(mask.len(), data.len(), "mask length must match data length");
+        Self { data, mask }
+    }
+
+    /// Keeps only items also set in `other` (AND).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other.len()` differs from the slice length.
+    pub fn and(mut self, other: &[bool]) -> Self {
+        self.check_len(other);
+        for (flag, other) in self.mask.iter_mut().zip(other) {
+            *flag &= *other;
+        }
+        self
+    }
+
+    /// Adds items set in `other` (OR).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other.len()` differs from the slice length.
+    pub fn or(mut self, other: &[bool]) -> Self {
+        self.check_len(other);
+        for (flag, other) in self.mask.iter_mut().zip(other) {
+            *flag |= *other;
+        }
+        self
+    }
+
+    /// Inverts the mask (NOT).
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(mut self) -> Self {
+        for flag in self.mask.iter_mut() {
+            *flag = !*flag;
+        }
+        self
+    }
+
+    /// Returns a copy of the current mask, one flag per item.
+    pub fn mask(&self) -> Vec<bool> {
+        self.mask.clone()
+    }
+
+    /// Returns all items set in the mask, in slice order.
+    pub fn all(&self) -> Vec<&'a T> {
+        self.data
+            .iter()
+            .zip(&self.mask)
+            .filter(|(_, &keep)| keep)
+            .map(|(item, _)| item)
+            .collect()
+    }
+
+    /// Counts the items set in the mask.
+    pub fn count(&self) -> usize {
+        self.mask.iter().filter(|&&keep| keep).count()
+    }
+
+    /// Converts the plan into a `Query` for further filtering and aggregation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let total = plan.into_query().sum(Product::price());
+    /// ```
+    pub fn into_query(self) -> Query<'a, T> {
+        Query::from_mask(self.data, self.mask)
+    }
+
+    fn check_len(&self, other: &[bool]) {
+        assert_eq!(other.len(), self.mask.len(), "mask length must match data length");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use key_paths_derive::Keypath;
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct User {
+        id: u32,
+        active: bool,
+        role: String,
+        age: u32,
+    }
+
+    fn users() -> Vec<User> {
+        (0..20)
+            .map(|id| User {
+                id,
+                active: id % 3 != 0,
+                role: if id % 4 == 0 { "admin" } else { "member" }.to_string(),
+                age: 18 + id * 2,
+            })
+            .collect()
+    }
+
+    fn ids(items: Vec<&User>) -> Vec<u32> {
+        items.iter().map(|u| u.id).collect()
+    }
+
+    #[test]
+    fn test_composed_masks_match_direct_queries() {
+        let users = users();
+        let active = Query::new(&users).where_(User::active(), |&a| a).mask();
+        let admin = Query::new(&users).where_(User::role(), |r| r == "admin").mask();
+
+        let and = QueryPlan::from_mask(&users, active.clone()).and(&admin);
+        let direct_and = Query::new(&users)
+            .where_(User::active(), |&a| a)
+            .where_(User::role(), |r| r == "admin");
+        assert_eq!(ids(and.all()), ids(direct_and.all()));
+        assert_eq!(and.count(), direct_and.count());
+
+        let or = QueryPlan::from_mask(&users, active.clone()).or(&admin);
+        let direct_or = Query::new(&users).where_item(|u| u.active || u.role == "admin");
+        assert_eq!(ids(or.all()), ids(direct_or.all()));
+
+        let not = QueryPlan::from_mask(&users, active).not().and(&admin);
+        let direct_not = Query::new(&users).where_item(|u| !u.active && u.role == "admin");
+        assert_eq!(ids(not.all()), ids(direct_not.all()));
+        assert_eq!(ids(not.all()), vec![0, 12]);
+    }
+
+    #[test]
+    fn test_from_mask_query_chains_filters() {
+        let users = users();
+        let active = Query::new(&users).where_(User::active(), |&a| a).mask();
+
+        let from_mask = Query::from_mask(&users, active.clone()).where_(User::age(), |&a| a > 40);
+        let direct = Query::new(&users)
+            .where_(User::active(), |&a| a)
+            .where_(User::age(), |&a| a > 40);
+        assert_eq!(ids(from_mask.all()), ids(direct.all()));
+        assert_eq!(from_mask.sum(User::age()), direct.sum(User::age()));
+
+        let plan = QueryPlan::from_query(&direct);
+        assert_eq!(plan.mask(), direct.mask());
+        assert_eq!(ids(plan.into_query().all()), ids(direct.all()));
+        assert_eq!(QueryPlan::new(&users).count(), users.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "mask length must match data length")]
+    fn test_from_mask_rejects_wrong_length() {
+        let users = users();
+        Query::from_mask(&users, vec![true; 3]);
+    }
+
+    #[test]
+    fn test_from_mask_uses_positions_for_zero_sized_items() {
+        let units = vec![(); 5];
+        let query = Query::from_mask(&units, vec![true, false, true, false, false]);
+        assert_eq!(query.count(), 2);
+        assert_eq!(query.indices(), vec![0, 2]);
+        assert_eq!(query.mask(), vec![true, false, true, false, false]);
+
+        let users = users();
+        let mask: Vec<bool> = users.iter().map(|u| u.id % 5 == 0).collect();
+        let query = Query::from_mask(&users, mask).where_(User::active(), |&a| a);
+        assert_eq!(query.indices(), vec![5, 10]);
+        assert_eq!(query.argmax(User::age()), Some(10));
+
+        let (passed, rejected) = query.partition_explained();
+        assert_eq!(ids(passed), vec![5, 10]);
+        assert_eq!(rejected.iter().filter(|(_, reason)| *reason == "masked").count(), 16);
+    }
+}
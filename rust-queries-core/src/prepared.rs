@@ -0,0 +1,176 @@
+//! Reusable filter chains that are not bound to any data.
+//!
+//! This module provides `PreparedQuery`, which is built once and can then be
+//! run against any number of slices, such as a `Vec` that keeps growing.
+
+use key_paths_core::KeyPaths;
+
+type Filter<T> = Box<dyn Fn(&T) -> bool>;
+
+/// A filter chain defined independently of the data it runs against.
+///
+/// Build it once with the same `where_` calls as `Query`, then call `run()`
+/// on whichever slice is current.
+///
+/// # Example
+///
+/// ```ignore
+/// let alerts = PreparedQuery::new()
+///     .where_(Event::level(), |l| l == "error")
+///     .where_(Event::service(), |s| s == "billing");
+///
+/// loop {
+///     events.extend(poll_new_events());
+///     let matching = alerts.run(&events);
+/// }
+/// ```
+pub struct PreparedQuery<T: 'static> {
+    filters: Vec<Filter<T>>,
+}
+
+impl<T: 'static> PreparedQuery<T> {
+    /// Creates an empty prepared query that matches every item.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let prepared = PreparedQuery::<Product>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+        }
+    }
+
+    /// Adds a filter predicate using a key-path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to filter on
+    /// * `predicate` - A function that returns true for items to keep
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let prepared = PreparedQuery::new().where_(Product::price(), |&p| p < 100.0);
+    /// ```
+    pub fn where_<F>(mut self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool + 'static) -> Self
+    where
+        F: 'static,
+    {
+        self.filters.push(Box::new(move |item| {
+            path.get(item).is_some_and(&predicate)
+        }));
+        self
+    }
+
+    /// Adds a filter predicate over the whole item.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let prepared = PreparedQuery::new().where_item(|p: &Product| p.price > p.cost);
+    /// ```
+    pub fn where_item(mut self, predicate: impl Fn(&T) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Runs the filters against a slice and returns the matching items.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cheap = prepared.run(&products);
+    /// ```
+    pub fn run<'a>(&self, data: &'a [T]) -> Vec<&'a T> {
+        data.iter().filter(|item| self.matches(item)).collect()
+    }
+
+    /// Counts the items in a slice that match the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let count = prepared.count(&products);
+    /// ```
+    pub fn count(&self, data: &[T]) -> usize {
+        data.iter().filter(|item| self.matches(item)).count()
+    }
+
+    /// Checks whether a single item matches the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if prepared.matches(&new_product) {
+    ///     notify(&new_product);
+    /// }
+    /// ```
+    pub fn matches(&self, item: &T) -> bool {
+        self.filters.iter().all(|f| f(item))
+    }
+}
+
+impl<T: 'static> Default for PreparedQuery<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Query;
+    use key_paths_derive::Keypath;
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Event {
+        id: u32,
+        level: String,
+        latency_ms: u32,
+    }
+
+    fn events(n: u32) -> Vec<Event> {
+        (0..n)
+            .map(|id| Event {
+                id,
+                level: if id % 4 == 0 { "error" } else { "info" }.to_string(),
+                latency_ms: id * 13 % 100,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_prepared_query_runs_against_growing_data() {
+        let prepared = PreparedQuery::new()
+            .where_(Event::level(), |l| l == "error")
+            .where_item(|e: &Event| e.latency_ms > 20);
+
+        let mut data = events(10);
+        let first: Vec<u32> = prepared.run(&data).iter().map(|e| e.id).collect();
+        let direct: Vec<u32> = Query::new(&data)
+            .where_(Event::level(), |l| l == "error")
+            .where_(Event::latency_ms(), |&l| l > 20)
+            .all()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(first, direct);
+        assert_eq!(first, vec![4]);
+
+        data.extend(events(40).into_iter().skip(10));
+        let second: Vec<u32> = prepared.run(&data).iter().map(|e| e.id).collect();
+        assert_eq!(second, vec![4, 12, 20, 28, 36]);
+        assert_eq!(prepared.count(&data), second.len());
+        assert!(prepared.matches(&data[4]));
+        assert!(!prepared.matches(&data[1]));
+    }
+
+    #[test]
+    fn test_empty_prepared_query_matches_everything() {
+        let data = events(5);
+        assert_eq!(PreparedQuery::default().run(&data).len(), 5);
+        assert!(PreparedQuery::<Event>::new().run(&[]).is_empty());
+    }
+}
@@ -4,8 +4,9 @@
 //! using type-safe key-paths.
 
 use key_paths_core::KeyPaths;
-use std::collections::HashMap;
-use std::time::SystemTime;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 #[cfg(feature = "datetime")]
 use chrono::{DateTime, TimeZone};
@@ -25,15 +26,29 @@ use chrono::{DateTime, TimeZone};
 ///     .where_(Product::price(), |&price| price < 100.0)
 ///     .order_by_float(Product::price());
 /// ```
+///
+/// Key-paths into nested structs work anywhere a field key-path does:
+///
+/// ```ignore
+/// let by_region = Query::new(&orders).group_by(Order::customer().then(Customer::region()));
+/// ```
 pub struct Query<'a, T: 'static> {
-    data: &'a [T],
+    pub(crate) data: &'a [T],
     filters: Vec<Box<dyn Fn(&T) -> bool>>,
+    labels: HashMap<usize, &'static str>,
+    mask: Option<Vec<bool>>,
 }
 
 // Core implementation without Clone requirement
 impl<'a, T: 'static> Query<'a, T> {
     /// Creates a new query from a slice of data.
     ///
+    /// The slice may be borrowed from anything that outlives the query, such
+    /// as a read-only memory-mapped file viewed as `&[T]`; only the item type
+    /// has to be `'static` (own its data), not the slice. Results returned by
+    /// `all`, `first` and `limit` borrow from the slice rather than from the
+    /// query, so they remain usable after the query is dropped.
+    ///
     /// # Arguments
     ///
     /// * `data` - A slice of items to query
@@ -42,14 +57,69 @@ impl<'a, T: 'static> Query<'a, T> {
     ///
     /// ```ignore
     /// let query = Query::new(&products);
+    ///
+    /// // A slice over a memory-mapped file of fixed-size records
+    /// let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    /// let ticks: &[Tick] = bytemuck::cast_slice(&mmap);
+    /// let volume = Query::new(ticks).where_(Tick::symbol(), |&s| s == AAPL_ID).sum(Tick::volume());
     /// ```
     pub fn new(data: &'a [T]) -> Self {
         Self {
             data,
             filters: Vec::new(),
+            labels: HashMap::new(),
+            mask: None,
         }
     }
 
+    /// Creates a query over `data` that only matches items whose mask entry is `true`.
+    ///
+    /// Use with `mask()` or `QueryPlan` to reuse a precomputed filter across
+    /// many queries on the same slice. Further filters can be chained as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A slice of items to query
+    /// * `mask` - One flag per item in `data`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask.len()` differs from `data.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let active = Query::new(&users).where_(User::active(), |&a| a).mask();
+    /// let admins = Query::from_mask(&users, active.clone())
+    ///     .where_(User::role(), |r| r == "admin")
+    ///     .all();
+    /// ```
+    pub fn from_mask(data: &'a [T], mask: Vec<bool>) -> Self {
+        assert_eq!(mask.len(), data.len(), "mask length must match data length");
+
+        let mut query = Self::new(data);
+        query.mask = Some(mask);
+        query
+    }
+
+    /// Checks the item at `index` in the data against the mask and every filter.
+    fn matches_at(&self, index: usize, item: &T) -> bool {
+        let unmasked = match &self.mask {
+            Some(mask) => mask[index],
+            None => true,
+        };
+        unmasked && self.filters.iter().all(|f| f(item))
+    }
+
+    /// Iterates over the matching items in source order.
+    fn matching(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, item)| self.matches_at(*index, item))
+            .map(|(_, item)| item)
+    }
+
     /// Adds a filter predicate using a key-path.
     ///
     /// # Arguments
@@ -73,1249 +143,5985 @@ impl<'a, T: 'static> Query<'a, T> {
         self
     }
 
-    /// Returns all items matching the query filters.
+    /// Adds a filter predicate using a key-path, tagged with a label.
     ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// let results = query.all();
-    /// ```
-    pub fn all(&self) -> Vec<&T> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .collect()
-    }
-
-    /// Returns the first item matching the query filters.
+    /// Behaves exactly like `where_`; the label is reported by
+    /// `partition_explained` for items rejected by this filter.
     ///
-    /// # Example
+    /// # Arguments
     ///
-    /// ```ignore
-    /// let first = query.first();
-    /// ```
-    pub fn first(&self) -> Option<&T> {
-        self.data
-            .iter()
-            .find(|item| self.filters.iter().all(|f| f(item)))
-    }
-
-    /// Returns the count of items matching the query filters.
+    /// * `label` - A short reason describing the filter
+    /// * `path` - The key-path to the field to filter on
+    /// * `predicate` - A function that returns true for items to keep
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let count = query.count();
+    /// let query = Query::new(&records)
+    ///     .where_labeled("missing email", Record::email(), |e| !e.is_empty())
+    ///     .where_labeled("negative amount", Record::amount(), |&a| a >= 0.0);
     /// ```
-    pub fn count(&self) -> usize {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .count()
+    pub fn where_labeled<F>(
+        mut self,
+        label: &'static str,
+        path: KeyPaths<T, F>,
+        predicate: impl Fn(&F) -> bool + 'static,
+    ) -> Self
+    where
+        F: 'static,
+    {
+        self.labels.insert(self.filters.len(), label);
+        self.where_(path, predicate)
     }
 
-    /// Returns the first `n` items matching the query filters.
+    /// Adds a filter predicate over the whole item.
+    ///
+    /// Useful for conditions spanning several fields, which a single key-path
+    /// predicate can't express.
     ///
     /// # Arguments
     ///
-    /// * `n` - The maximum number of items to return
+    /// * `predicate` - A function that returns true for items to keep
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let first_10 = query.limit(10);
+    /// let profitable = Query::new(&products)
+    ///     .where_item(|p| p.price > p.cost);
     /// ```
-    pub fn limit(&self, n: usize) -> Vec<&T> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .take(n)
-            .collect()
+    pub fn where_item(mut self, predicate: impl Fn(&T) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
     }
 
-    /// Skips the first `offset` items for pagination.
+    /// Adds a thread-safe filter built with `SyncFilter`.
+    ///
+    /// The filter is shared, not copied, so the same `SyncFilter` can be
+    /// cloned into many worker threads and applied there. The resulting
+    /// `Query` is still not `Send`; to move a whole query to another thread,
+    /// build it with `SyncQuery` instead.
     ///
     /// # Arguments
     ///
-    /// * `offset` - The number of items to skip
+    /// * `filter` - The filter to apply
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let page_2 = query.skip(20).limit(10);
+    /// let cheap = SyncFilter::new(|p: &Product| p.price < 100.0);
+    /// let results = Query::new(&products).where_sync(&cheap).all();
     /// ```
-    pub fn skip<'b>(&'b self, offset: usize) -> QueryWithSkip<'a, 'b, T> {
-        QueryWithSkip {
-            query: self,
-            offset,
-        }
+    pub fn where_sync(mut self, filter: &SyncFilter<T>) -> Self {
+        let filter = filter.clone();
+        self.filters.push(Box::new(move |item| filter.matches(item)));
+        self
     }
 
-    /// Projects/selects a single field from results.
+    /// Filters by a field being greater than a value (`>`).
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the field to select
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let names = query.select(Product::name());
+    /// let results = query.where_gt(Product::stock(), 10).all();
     /// ```
-    pub fn select<F>(&self, path: KeyPaths<T, F>) -> Vec<F>
+    pub fn where_gt<F>(self, path: KeyPaths<T, F>, value: F) -> Self
     where
-        F: Clone + 'static,
+        F: PartialOrd + 'static,
     {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .collect()
+        self.where_(path, move |field| *field > value)
     }
 
-    /// Computes the sum of a numeric field.
+    /// Filters by a field being greater than or equal to a value (`>=`).
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the numeric field
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let total_price = query.sum(Product::price());
+    /// let results = query.where_ge(Product::stock(), 10).all();
     /// ```
-    pub fn sum<F>(&self, path: KeyPaths<T, F>) -> F
+    pub fn where_ge<F>(self, path: KeyPaths<T, F>, value: F) -> Self
     where
-        F: Clone + std::ops::Add<Output = F> + Default + 'static,
+        F: PartialOrd + 'static,
     {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .fold(F::default(), |acc, val| acc + val)
+        self.where_(path, move |field| *field >= value)
     }
 
-    /// Computes the average of a float field.
+    /// Filters by a field being less than a value (`<`).
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the f64 field
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let avg_price = query.avg(Product::price()).unwrap_or(0.0);
+    /// let results = query.where_lt(Product::stock(), 10).all();
     /// ```
-    pub fn avg(&self, path: KeyPaths<T, f64>) -> Option<f64> {
-        let items: Vec<f64> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .collect();
-
-        if items.is_empty() {
-            None
-        } else {
-            Some(items.iter().sum::<f64>() / items.len() as f64)
-        }
+    pub fn where_lt<F>(self, path: KeyPaths<T, F>, value: F) -> Self
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_(path, move |field| *field < value)
     }
 
-    /// Finds the minimum value of a field.
+    /// Filters by a field being less than or equal to a value (`<=`).
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the field
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let min_stock = query.min(Product::stock());
+    /// let results = query.where_le(Product::stock(), 10).all();
     /// ```
-    pub fn min<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    pub fn where_le<F>(self, path: KeyPaths<T, F>, value: F) -> Self
     where
-        F: Ord + Clone + 'static,
+        F: PartialOrd + 'static,
     {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .min()
+        self.where_(path, move |field| *field <= value)
     }
 
-    /// Finds the maximum value of a field.
+    /// Filters by a field being equal to a value (`=`).
+    ///
+    /// Uses exact `PartialEq` comparison; for floats, values that differ only by
+    /// rounding error are not equal, so prefer a range check for computed values.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the field
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let max_stock = query.max(Product::stock());
+    /// let results = query.where_eq(Product::stock(), 10).all();
     /// ```
-    pub fn max<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    pub fn where_eq<F>(self, path: KeyPaths<T, F>, value: F) -> Self
     where
-        F: Ord + Clone + 'static,
+        F: PartialEq + 'static,
     {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .max()
+        self.where_(path, move |field| *field == value)
     }
 
-    /// Finds the minimum value of a float field.
+    /// Filters by a field being not equal to a value (`<>`).
+    ///
+    /// Compares exactly; see [`where_eq`](Self::where_eq) for floats.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the f64 field
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to compare against
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let min_price = query.min_float(Product::price());
+    /// let results = query.where_ne(Product::stock(), 10).all();
     /// ```
-    pub fn min_float(&self, path: KeyPaths<T, f64>) -> Option<f64> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    pub fn where_ne<F>(self, path: KeyPaths<T, F>, value: F) -> Self
+    where
+        F: PartialEq + 'static,
+    {
+        self.where_(path, move |field| *field != value)
     }
 
-    /// Finds the maximum value of a float field.
+    /// Filters by comparing two fields of the same item.
+    ///
+    /// Keeps items where `a` compares to `b` with the given ordering, e.g.
+    /// `Ordering::Less` keeps items with `a < b`. Items where either key-path
+    /// yields `None`, or the values are incomparable (e.g. `NaN`), are excluded.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the f64 field
+    /// * `a` - The key-path to the left-hand field
+    /// * `b` - The key-path to the right-hand field
+    /// * `ord` - The required ordering of `a` relative to `b`
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let max_price = query.max_float(Product::price());
+    /// // WHERE discounted_price < original_price
+    /// let on_sale = query.where_fields_cmp(Product::discounted_price(), Product::original_price(), Ordering::Less);
     /// ```
-    pub fn max_float(&self, path: KeyPaths<T, f64>) -> Option<f64> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    pub fn where_fields_cmp<F>(self, a: KeyPaths<T, F>, b: KeyPaths<T, F>, ord: std::cmp::Ordering) -> Self
+    where
+        F: PartialOrd + 'static,
+    {
+        self.where_item(move |item| match (a.get(item), b.get(item)) {
+            (Some(a), Some(b)) => a.partial_cmp(b) == Some(ord),
+            _ => false,
+        })
     }
 
-    /// Checks if any items match the query filters.
+    /// Filters by two fields of the same item being equal.
     ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// let has_results = query.exists();
-    /// ```
-    pub fn exists(&self) -> bool {
-        self.data
-            .iter()
-            .any(|item| self.filters.iter().all(|f| f(item)))
-    }
-
-    // DateTime operations for SystemTime
-    /// Filter by SystemTime being after a reference time.
+    /// Items where either key-path yields `None` are excluded.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the SystemTime field
-    /// * `reference` - The reference time to compare against
+    /// * `a` - The key-path to the first field
+    /// * `b` - The key-path to the second field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_after_systemtime(Event::timestamp(), &cutoff_time);
+    /// // WHERE billing_country = shipping_country
+    /// let domestic = query.where_fields_eq(Order::billing_country(), Order::shipping_country());
     /// ```
-    pub fn where_after_systemtime(self, path: KeyPaths<T, SystemTime>, reference: SystemTime) -> Self {
-        self.where_(path, move |time| time > &reference)
+    pub fn where_fields_eq<F>(self, a: KeyPaths<T, F>, b: KeyPaths<T, F>) -> Self
+    where
+        F: PartialEq + 'static,
+    {
+        self.where_item(move |item| match (a.get(item), b.get(item)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        })
     }
 
-    /// Filter by SystemTime being before a reference time.
+    /// Filters by a `Copy` field, such as a fieldless enum, being equal to a value.
+    ///
+    /// Same as `where_eq`, named to read naturally for enum columns.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the SystemTime field
-    /// * `reference` - The reference time to compare against
+    /// * `path` - The key-path to the field to compare
+    /// * `value` - The value to match
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let old = query.where_before_systemtime(Event::timestamp(), &cutoff_time);
+    /// let active = query.where_is(Account::status(), Status::Active);
     /// ```
-    pub fn where_before_systemtime(self, path: KeyPaths<T, SystemTime>, reference: SystemTime) -> Self {
-        self.where_(path, move |time| time < &reference)
+    pub fn where_is<F>(self, path: KeyPaths<T, F>, value: F) -> Self
+    where
+        F: Copy + PartialEq + 'static,
+    {
+        self.where_eq(path, value)
     }
 
-    /// Filter by SystemTime being between two times (inclusive).
+    /// Filters by a `Copy` field, such as a fieldless enum, being one of several values (`IN`).
+    ///
+    /// An empty `values` slice matches nothing.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the SystemTime field
-    /// * `start` - The start time
-    /// * `end` - The end time
+    /// * `path` - The key-path to the field to compare
+    /// * `values` - The accepted values
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let range = query.where_between_systemtime(Event::timestamp(), &start, &end);
+    /// let open = query.where_is_any(Ticket::status(), &[Status::New, Status::InProgress]);
     /// ```
-    pub fn where_between_systemtime(
-        self,
-        path: KeyPaths<T, SystemTime>,
-        start: SystemTime,
-        end: SystemTime,
-    ) -> Self {
-        self.where_(path, move |time| time >= &start && time <= &end)
+    pub fn where_is_any<F>(self, path: KeyPaths<T, F>, values: &[F]) -> Self
+    where
+        F: Copy + PartialEq + 'static,
+    {
+        let values = values.to_vec();
+        self.where_(path, move |field| values.contains(field))
     }
-}
 
-// DateTime operations with chrono (only available with datetime feature)
-#[cfg(feature = "datetime")]
-impl<'a, T: 'static> Query<'a, T> {
-    /// Filter by DateTime being after a reference time.
+    /// Filters to rows valid at a point in time: `from <= at <= to`.
+    ///
+    /// Both bounds are inclusive, so an open-ended row can use `i64::MAX` as
+    /// its `to` value. Rows where either key-path yields `None` are excluded.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `reference` - The reference time to compare against
+    /// * `from` - The key-path to the start of the validity window
+    /// * `to` - The key-path to the end of the validity window
+    /// * `at` - The point in time to query
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_after(Event::timestamp(), &cutoff_time);
+    /// let price_then = query.where_valid_at(Price::valid_from(), Price::valid_to(), ts);
     /// ```
-    pub fn where_after<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, reference: DateTime<Tz>) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        self.where_(path, move |time| time > &reference)
+    pub fn where_valid_at(self, from: KeyPaths<T, i64>, to: KeyPaths<T, i64>, at: i64) -> Self {
+        self.where_item(move |item| match (from.get(item), to.get(item)) {
+            (Some(&from), Some(&to)) => from <= at && at <= to,
+            _ => false,
+        })
     }
 
-    /// Filter by DateTime being before a reference time.
+    /// Filters by a Vec field containing at least one of the given values.
+    ///
+    /// An empty `any` set matches nothing. Note that `#[derive(Keypath)]` maps
+    /// Vec fields to their first element, so build the path to the whole Vec
+    /// with `KeyPaths::readable`.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `reference` - The reference time to compare against
+    /// * `path` - The key-path to the Vec field
+    /// * `any` - The values to look for
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let old = query.where_before(Event::timestamp(), &cutoff_time);
+    /// let tags = KeyPaths::readable(|p: &Post| &p.tags);
+    /// let tagged = query.where_vec_contains_any(tags, &["rust".to_string(), "sql".to_string()]);
     /// ```
-    pub fn where_before<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, reference: DateTime<Tz>) -> Self
+    pub fn where_vec_contains_any<E>(self, path: KeyPaths<T, Vec<E>>, any: &[E]) -> Self
     where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
+        E: PartialEq + Clone + 'static,
     {
-        self.where_(path, move |time| time < &reference)
+        let wanted = any.to_vec();
+        self.where_(path, move |values| wanted.iter().any(|w| values.contains(w)))
     }
 
-    /// Filter by DateTime being between two times (inclusive).
+    /// Filters by a Vec field containing every one of the given values.
+    ///
+    /// An empty `all` set matches every item with the field present.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `start` - The start time
-    /// * `end` - The end time
+    /// * `path` - The key-path to the Vec field
+    /// * `all` - The values that must all be present
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let range = query.where_between(Event::timestamp(), &start, &end);
+    /// let tags = KeyPaths::readable(|p: &Post| &p.tags);
+    /// let tagged = query.where_vec_contains_all(tags, &["rust".to_string(), "sql".to_string()]);
     /// ```
-    pub fn where_between<Tz>(
-        self,
-        path: KeyPaths<T, DateTime<Tz>>,
-        start: DateTime<Tz>,
-        end: DateTime<Tz>,
-    ) -> Self
+    pub fn where_vec_contains_all<E>(self, path: KeyPaths<T, Vec<E>>, all: &[E]) -> Self
     where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
+        E: PartialEq + Clone + 'static,
     {
-        self.where_(path, move |time| time >= &start && time <= &end)
+        let wanted = all.to_vec();
+        self.where_(path, move |values| wanted.iter().all(|w| values.contains(w)))
     }
 
-    /// Filter by DateTime being today.
+    /// Filters by a field whose value is in a set of keys, like SQL `IN (subquery)`.
+    ///
+    /// The keys are collected into a `HashSet` once, so each membership check is O(1).
+    /// Pair with `select` on another query to express IN-subqueries.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `now` - The current DateTime to compare against
+    /// * `path` - The key-path to the field to match
+    /// * `keys` - The allowed values
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let today = query.where_today(Event::timestamp(), &Utc::now());
+    /// // SELECT * FROM users WHERE id IN (SELECT user_id FROM orders WHERE status = 'completed')
+    /// let buyer_ids = Query::new(&orders)
+    ///     .where_(Order::status(), |s| s == "completed")
+    ///     .select(Order::user_id());
+    /// let buyers = Query::new(&users).where_in_subquery(User::id(), buyer_ids);
     /// ```
-    pub fn where_today<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, now: DateTime<Tz>) -> Self
+    pub fn where_in_subquery<F>(self, path: KeyPaths<T, F>, keys: impl IntoIterator<Item = F>) -> Self
     where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
+        F: Eq + std::hash::Hash + 'static,
     {
-        self.where_(path, move |time| {
-            time.date_naive() == now.date_naive()
-        })
+        let keys: HashSet<F> = keys.into_iter().collect();
+        self.where_(path, move |value| keys.contains(value))
     }
 
-    /// Filter by DateTime year.
+    /// Filters by a String field within a maximum edit distance of a target.
+    ///
+    /// Uses Levenshtein distance over characters, stopping early once an item
+    /// is known to exceed `max_distance`.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `year` - The year to filter by
+    /// * `path` - The key-path to the String field
+    /// * `target` - The string to compare against
+    /// * `max_distance` - The maximum number of insertions, deletions, or substitutions
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let this_year = query.where_year(Event::timestamp(), 2024);
+    /// // Matches "Laptop" despite the typo
+    /// let results = query.where_similar(Product::name(), "Lpatop", 2);
     /// ```
-    pub fn where_year<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, year: i32) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Datelike;
-        self.where_(path, move |time| time.year() == year)
+    pub fn where_similar(self, path: KeyPaths<T, String>, target: &str, max_distance: usize) -> Self {
+        let target: Vec<char> = target.chars().collect();
+        self.where_(path, move |value| within_distance(value, &target, max_distance))
     }
 
-    /// Filter by DateTime month.
+    /// Returns all items matching the query filters.
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `month` - The month to filter by (1-12)
+    /// ```ignore
+    /// let results = query.all();
+    /// ```
+    pub fn all(&self) -> Vec<&'a T> {
+        self.matching().collect()
+    }
+
+    /// Returns all matching items together with scan counts and elapsed time.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let december = query.where_month(Event::timestamp(), 12);
+    /// let (results, stats) = query.all_timed();
+    /// println!("{} of {} items in {:?}", stats.items_passed, stats.items_scanned, stats.elapsed);
     /// ```
-    pub fn where_month<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, month: u32) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Datelike;
-        self.where_(path, move |time| time.month() == month)
+    pub fn all_timed(&self) -> (Vec<&'a T>, QueryStats) {
+        let start = Instant::now();
+        let results: Vec<&'a T> = self.matching().collect();
+        let stats = QueryStats {
+            items_scanned: self.data.len(),
+            items_passed: results.len(),
+            elapsed: start.elapsed(),
+        };
+        (results, stats)
     }
 
-    /// Filter by DateTime day.
+    /// Runs any terminal operation and measures how long it takes.
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `path` - The key-path to the DateTime field
-    /// * `day` - The day to filter by (1-31)
+    /// ```ignore
+    /// let (total, elapsed) = query.timed(|q| q.sum(Product::price()));
+    /// ```
+    pub fn timed<R>(&self, f: impl FnOnce(&Self) -> R) -> (R, Duration) {
+        let start = Instant::now();
+        let result = f(self);
+        (result, start.elapsed())
+    }
+
+    /// Returns the first item matching the query filters.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let first = query.where_day(Event::timestamp(), 1);
+    /// let first = query.first();
     /// ```
-    pub fn where_day<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, day: u32) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Datelike;
-        self.where_(path, move |time| time.day() == day)
+    pub fn first(&self) -> Option<&'a T> {
+        self.matching().next()
     }
 
-    /// Filter by weekend dates (Saturday and Sunday).
-    ///
-    /// # Arguments
+    /// Applies `f` to matching items and returns the first `Some` result.
     ///
-    /// * `path` - The key-path to the DateTime field
+    /// Stops as soon as `f` returns `Some`, so later items are neither
+    /// filtered nor passed to `f`.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let weekend_events = query.where_weekend(Event::timestamp());
+    /// let first_error: Option<String> = Query::new(&orders)
+    ///     .where_(Order::status(), |s| s == "failed")
+    ///     .find_map(|order| order.error.clone());
     /// ```
-    pub fn where_weekend<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Datelike;
-        self.where_(path, |time| {
-            let weekday = time.weekday().num_days_from_monday();
-            weekday >= 5
-        })
+    pub fn find_map<U>(&self, f: impl Fn(&T) -> Option<U>) -> Option<U> {
+        self.matching().find_map(f)
     }
 
-    /// Filter by weekday dates (Monday through Friday).
+    /// Folds every matching item into a caller-defined accumulator in a single pass.
+    ///
+    /// Useful for computing several metrics at once without scanning the
+    /// data once per aggregation.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the DateTime field
+    /// * `init` - The initial accumulator value
+    /// * `step` - Updates the accumulator with one matching item
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let weekday_events = query.where_weekday(Event::timestamp());
+    /// #[derive(Default)]
+    /// struct Report { orders: usize, revenue: f64, users: HashSet<u32> }
+    ///
+    /// let report = query.fold_report(Report::default(), |r, order| {
+    ///     r.orders += 1;
+    ///     r.revenue += order.total;
+    ///     r.users.insert(order.user_id);
+    /// });
     /// ```
-    pub fn where_weekday<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Datelike;
-        self.where_(path, |time| {
-            let weekday = time.weekday().num_days_from_monday();
-            weekday < 5
-        })
+    pub fn fold_report<R>(&self, init: R, step: impl Fn(&mut R, &T)) -> R {
+        let mut report = init;
+        for item in self.matching() {
+            step(&mut report, item);
+        }
+        report
     }
 
-    /// Filter by business hours (9 AM - 5 PM).
+    /// Returns the first `N` matching items in a fixed-size array, without allocating.
     ///
-    /// # Arguments
-    ///
-    /// * `path` - The key-path to the DateTime field
+    /// Slots past the last match are `None`. Iteration stops once `N` matches
+    /// have been found.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let business_hours = query.where_business_hours(Event::timestamp());
+    /// let [first, second, third] = query.first_array::<3>();
     /// ```
-    pub fn where_business_hours<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
-    where
-        Tz: TimeZone + 'static,
-        Tz::Offset: std::fmt::Display,
-    {
-        use chrono::Timelike;
-        self.where_(path, |time| {
-            let hour = time.hour();
-            hour >= 9 && hour < 17
-        })
+    pub fn first_array<const N: usize>(&self) -> [Option<&'a T>; N] {
+        let mut slots = [None; N];
+        let matches = self.matching();
+        for (slot, item) in slots.iter_mut().zip(matches) {
+            *slot = Some(item);
+        }
+        slots
     }
-}
 
-// Operations that require Clone - separated for flexibility
-impl<'a, T: 'static + Clone> Query<'a, T> {
-    /// Orders results by a field in ascending order.
-    /// 
-    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
-    ///
-    /// # Arguments
+    /// Returns the count of items matching the query filters.
     ///
-    /// * `path` - The key-path to the field to order by
+    /// Returns `0` if no items match.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by(Product::name());
+    /// let count = query.count();
     /// ```
-    pub fn order_by<F>(&self, path: KeyPaths<T, F>) -> Vec<T>
-    where
-        F: Ord + Clone + 'static,
-    {
-        let mut results: Vec<T> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
-            .collect();
-
-        results.sort_by_key(|item| path.get(item).cloned());
-        results
+    pub fn count(&self) -> usize {
+        self.matching().count()
     }
 
-    /// Orders results by a field in descending order.
-    /// 
-    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    /// Counts matching items per bucket in a single pass, like `COUNT(CASE WHEN ...)` columns.
+    ///
+    /// Buckets may overlap: an item is counted in every bucket whose predicate it
+    /// satisfies. Every bucket appears in the result, with `0` if nothing matched.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the field to order by
+    /// * `buckets` - Pairs of bucket name and predicate
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by_desc(Product::stock());
+    /// let segments = query.count_by_predicate(&[
+    ///     ("cheap", Box::new(|p: &Product| p.price < 50.0)),
+    ///     ("in_stock", Box::new(|p: &Product| p.stock > 0)),
+    /// ]);
     /// ```
-    pub fn order_by_desc<F>(&self, path: KeyPaths<T, F>) -> Vec<T>
-    where
-        F: Ord + Clone + 'static,
-    {
-        let mut results: Vec<T> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
-            .collect();
+    #[allow(clippy::type_complexity)]
+    pub fn count_by_predicate<'b>(&self, buckets: &[(&'b str, Box<dyn Fn(&T) -> bool>)]) -> HashMap<&'b str, usize> {
+        let mut counts: HashMap<&'b str, usize> = buckets.iter().map(|(name, _)| (*name, 0)).collect();
 
-        results.sort_by(|a, b| {
-            let a_val = path.get(a).cloned();
-            let b_val = path.get(b).cloned();
-            b_val.cmp(&a_val)
-        });
-        results
+        for item in self.matching() {
+            for (name, predicate) in buckets {
+                if predicate(item) {
+                    *counts.entry(name).or_default() += 1;
+                }
+            }
+        }
+
+        counts
     }
 
-    /// Orders results by a float field in ascending order.
-    /// 
-    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    /// Counts matching items at or below each threshold in a single pass.
+    ///
+    /// Returns one cumulative count per threshold, in the order given, so
+    /// `[100.0, 200.0, 500.0]` yields how many values are `<= 100`, `<= 200`
+    /// and `<= 500`. Each value is placed with a binary search over the sorted
+    /// thresholds. NaN values are never counted, and a NaN threshold counts `0`.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the f64 field to order by
+    /// * `path` - The key-path to the f64 field
+    /// * `thresholds` - The upper bounds to count against, in any order
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by_float(Product::price());
+    /// let sla = query.count_le_thresholds(Request::latency_ms(), &[100.0, 200.0, 500.0]);
     /// ```
-    pub fn order_by_float(&self, path: KeyPaths<T, f64>) -> Vec<T> {
-        let mut results: Vec<T> = self
-            .data
+    pub fn count_le_thresholds(&self, path: KeyPaths<T, f64>, thresholds: &[f64]) -> Vec<usize> {
+        let mut sorted: Vec<f64> = thresholds.iter().copied().filter(|t| !t.is_nan()).collect();
+        sorted.sort_by(f64::total_cmp);
+
+        // buckets[i] counts values in (sorted[i - 1], sorted[i]].
+        let mut buckets = vec![0usize; sorted.len() + 1];
+        let values = self.matching()
+            .filter_map(|item| path.get(item).copied())
+            .filter(|value| !value.is_nan());
+        for value in values {
+            buckets[sorted.partition_point(|&t| t < value)] += 1;
+        }
+        let cumulative: Vec<usize> = buckets
             .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
+            .scan(0, |total, &n| {
+                *total += n;
+                Some(*total)
+            })
             .collect();
 
-        results.sort_by(|a, b| {
-            let a_val = path.get(a).cloned().unwrap_or(0.0);
-            let b_val = path.get(b).cloned().unwrap_or(0.0);
-            a_val.partial_cmp(&b_val).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        results
+        thresholds
+            .iter()
+            .map(|t| {
+                if t.is_nan() {
+                    0
+                } else {
+                    cumulative[sorted.partition_point(|&s| s < *t)]
+                }
+            })
+            .collect()
     }
 
-    /// Orders results by a float field in descending order.
-    /// 
-    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    /// Estimates the number of distinct values of a field using a HyperLogLog sketch.
+    ///
+    /// Memory use is fixed at 16 KiB regardless of the number of items. The
+    /// relative standard error is about 0.8%, so estimates are typically within
+    /// 2.5% of the exact count; small counts are close to exact. Items without
+    /// a value are skipped. Use this instead of collecting a `HashSet` when the
+    /// exact set would be too large.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the f64 field to order by
+    /// * `path` - The key-path to the field whose distinct values are counted
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by_float_desc(Product::rating());
+    /// let visitors = Query::new(&page_views).approx_count_distinct(PageView::user_id());
     /// ```
-    pub fn order_by_float_desc(&self, path: KeyPaths<T, f64>) -> Vec<T> {
-        let mut results: Vec<T> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
-            .collect();
-
-        results.sort_by(|a, b| {
-            let a_val = path.get(a).cloned().unwrap_or(0.0);
-            let b_val = path.get(b).cloned().unwrap_or(0.0);
-            b_val.partial_cmp(&a_val).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        results
+    #[cfg(feature = "hll")]
+    pub fn approx_count_distinct<F>(&self, path: KeyPaths<T, F>) -> u64
+    where
+        F: std::hash::Hash + 'static,
+    {
+        let mut sketch = crate::hll::HyperLogLog::new();
+        self.matching()
+            .filter_map(|item| path.get(item))
+            .for_each(|value| sketch.insert(value));
+        sketch.estimate()
     }
 
-    /// Groups results by a field value.
-    /// 
-    /// **Note**: This method requires `T: Clone` as it creates owned copies in groups.
+    /// Counts the distinct values of a field within each group, like `COUNT(DISTINCT value) ... GROUP BY key`.
+    ///
+    /// Built in one pass. Items whose key or value is missing are skipped, so
+    /// a group whose items all lack a value does not appear.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the field to group by
+    /// * `key` - The key-path to group by
+    /// * `value` - The key-path to the field whose distinct values are counted
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let by_category = query.group_by(Product::category());
+    /// // Distinct products purchased per customer
+    /// let variety = Query::new(&purchases).count_distinct_by_group(Purchase::customer_id(), Purchase::product_id());
     /// ```
-    pub fn group_by<F>(&self, path: KeyPaths<T, F>) -> HashMap<F, Vec<T>>
+    pub fn count_distinct_by_group<K, F>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, F>) -> HashMap<K, usize>
     where
+        K: Eq + std::hash::Hash + Clone + 'static,
         F: Eq + std::hash::Hash + Clone + 'static,
     {
-        let mut groups: HashMap<F, Vec<T>> = HashMap::new();
+        let mut distinct: HashMap<K, HashSet<F>> = HashMap::new();
 
-        for item in self.data.iter() {
-            if self.filters.iter().all(|f| f(item)) {
-                if let Some(key) = path.get(item).cloned() {
-                    groups.entry(key).or_insert_with(Vec::new).push(item.clone());
-                }
+        for item in self.matching() {
+            if let (Some(group), Some(v)) = (key.get(item), value.get(item)) {
+                distinct.entry(group.clone()).or_default().insert(v.clone());
             }
         }
 
-        groups
+        distinct.into_iter().map(|(group, values)| (group, values.len())).collect()
     }
 
-    // ============================================================================
-    // i64 DateTime Aggregators (Unix timestamps in milliseconds)
-    // ============================================================================
-
-    /// Finds the minimum i64 timestamp value.
+    /// Returns the first matching item for each distinct key, in source order.
+    ///
+    /// The equivalent of Postgres' `SELECT DISTINCT ON (key)`: one whole row
+    /// per key, taken from its first occurrence. Runs in a single pass over
+    /// the data. Items whose key is missing are skipped.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `key` - The key-path identifying duplicates
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let earliest = query.min_timestamp(Event::created_at());
+    /// // Earliest login per user, assuming `logins` is sorted by time
+    /// let first_logins = Query::new(&logins).first_per_key(Login::user_id());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn min_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .min()
+    pub fn first_per_key<K>(&self, key: KeyPaths<T, K>) -> Vec<&'a T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut seen: HashSet<&K> = HashSet::new();
+        self.matching()
+            .filter(|item| key.get(item).is_some_and(|k| seen.insert(k)))
+            .collect()
     }
 
-    /// Finds the maximum i64 timestamp value.
+    /// Finds the group with the most matching items, without storing the groups.
+    ///
+    /// Only a count per key is kept. Ties go to the key seen first. Items
+    /// whose key is missing are skipped.
+    ///
+    /// Returns `None` if no items match.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `key` - The key-path to group by
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let latest = query.max_timestamp(Event::created_at());
+    /// if let Some((category, size)) = query.largest_group(Product::category()) {
+    ///     println!("{} has the most products ({})", category, size);
+    /// }
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn max_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .max()
+    pub fn largest_group<F>(&self, key: KeyPaths<T, F>) -> Option<(F, usize)>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        // Count and first-seen position per key
+        let mut tallies: HashMap<&F, (usize, usize)> = HashMap::new();
+        for item in self.matching() {
+            if let Some(group) = key.get(item) {
+                let next = tallies.len();
+                tallies.entry(group).or_insert((0, next)).0 += 1;
+            }
+        }
+
+        tallies
+            .into_iter()
+            .max_by(|(_, (count_a, seen_a)), (_, (count_b, seen_b))| {
+                count_a.cmp(count_b).then(seen_b.cmp(seen_a))
+            })
+            .map(|(group, (count, _))| (group.clone(), count))
     }
 
-    /// Calculates the average of i64 timestamp values.
+    /// Sums a float field per group and reports each group's share of the grand total.
+    ///
+    /// Returns `(sum, fraction_of_total)` per group; the fractions add up to
+    /// `1.0` up to rounding. If the grand total is `0.0`, every fraction is
+    /// `0.0` rather than NaN. Items whose key or value is missing are skipped.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `key` - The key-path to group by
+    /// * `value` - The key-path to the f64 field to sum
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let avg_timestamp = query.avg_timestamp(Event::created_at()).unwrap_or(0);
+    /// // Revenue share per region for a pie chart
+    /// let shares = query.group_share(Order::region(), Order::total());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn avg_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        let items: Vec<i64> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .collect();
-
-        if items.is_empty() {
-            None
-        } else {
-            Some(items.iter().sum::<i64>() / items.len() as i64)
+    pub fn group_share<K>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, f64>) -> HashMap<K, (f64, f64)>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut sums: HashMap<K, f64> = HashMap::new();
+        for item in self.matching() {
+            if let (Some(group), Some(v)) = (key.get(item), value.get(item)) {
+                *sums.entry(group.clone()).or_default() += v;
+            }
         }
+
+        let total: f64 = sums.values().sum();
+        sums.into_iter()
+            .map(|(group, sum)| {
+                let share = if total == 0.0 { 0.0 } else { sum / total };
+                (group, (sum, share))
+            })
+            .collect()
     }
 
-    /// Calculates the sum of i64 timestamp values.
+    /// Returns the first `n` items matching the query filters.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `n` - The maximum number of items to return
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let total_timestamp = query.sum_timestamp(Event::created_at());
+    /// let first_10 = query.limit(10);
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn sum_timestamp(&self, path: KeyPaths<T, i64>) -> i64 {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter_map(|item| path.get(item).cloned())
-            .sum()
+    pub fn limit(&self, n: usize) -> Vec<&'a T> {
+        self.matching()
+            .take(n)
+            .collect()
     }
 
-    /// Counts the number of non-null i64 timestamp values.
+    /// Skips the first `offset` items for pagination.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `offset` - The number of items to skip
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let timestamp_count = query.count_timestamp(Event::created_at());
+    /// let page_2 = query.skip(20).limit(10);
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn count_timestamp(&self, path: KeyPaths<T, i64>) -> usize {
-        self.data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .filter(|item| path.get(item).is_some())
-            .count()
+    pub fn skip<'b>(&'b self, offset: usize) -> QueryWithSkip<'a, 'b, T> {
+        QueryWithSkip {
+            query: self,
+            offset,
+        }
     }
 
-    /// Filters by i64 timestamp being after a reference timestamp.
+    /// Computes a derived value once per matching item for reuse across operations.
+    ///
+    /// `f` runs exactly once for each item that passes the current filters;
+    /// the results are cached alongside the items in the returned
+    /// `ComputedQuery`, so filtering and aggregating on the derived value
+    /// never recomputes it.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `reference` - The reference timestamp to compare against
+    /// * `f` - The derivation to compute for each matching item
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_after_timestamp(Event::created_at(), cutoff_timestamp);
+    /// let margins = query.with_computed(|p| (p.price - p.cost) / p.price);
+    /// let healthy = margins.where_computed(|&m| m > 0.3);
+    /// println!("{} products, average margin {:?}", healthy.count(), healthy.avg_computed());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_after_timestamp(self, path: KeyPaths<T, i64>, reference: i64) -> Self {
-        self.where_(path, move |timestamp| timestamp > &reference)
+    pub fn with_computed<U>(&self, f: impl Fn(&T) -> U) -> ComputedQuery<'a, T, U> {
+        ComputedQuery {
+            rows: self.matching()
+                .map(|item| (item, f(item)))
+                .collect(),
+        }
     }
 
-    /// Filters by i64 timestamp being before a reference timestamp.
+    /// Projects/selects a single field from results.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `reference` - The reference timestamp to compare against
+    /// * `path` - The key-path to the field to select
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let old = query.where_before_timestamp(Event::created_at(), cutoff_timestamp);
+    /// let names = query.select(Product::name());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_before_timestamp(self, path: KeyPaths<T, i64>, reference: i64) -> Self {
-        self.where_(path, move |timestamp| timestamp < &reference)
+    pub fn select<F>(&self, path: KeyPaths<T, F>) -> Vec<F>
+    where
+        F: Clone + 'static,
+    {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .collect()
     }
 
-    /// Filters by i64 timestamp being between two timestamps (inclusive).
+    /// Projects two fields and combines them into an output value in one pass.
+    ///
+    /// Items missing either field are skipped.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `start` - The start timestamp
-    /// * `end` - The end timestamp
+    /// * `a` - The key-path to the first field
+    /// * `b` - The key-path to the second field
+    /// * `f` - Function building the output from both field values
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let range = query.where_between_timestamp(Event::created_at(), start_ts, end_ts);
+    /// let summaries = query.select_map2(Product::id(), Product::name(), |id, name| {
+    ///     ProductSummary { id, name }
+    /// });
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_between_timestamp(self, path: KeyPaths<T, i64>, start: i64, end: i64) -> Self {
-        self.where_(path, move |timestamp| timestamp >= &start && timestamp <= &end)
+    pub fn select_map2<A, B, R>(&self, a: KeyPaths<T, A>, b: KeyPaths<T, B>, f: impl Fn(A, B) -> R) -> Vec<R>
+    where
+        A: Clone + 'static,
+        B: Clone + 'static,
+    {
+        self.matching()
+            .filter_map(|item| Some(f(a.get(item)?.clone(), b.get(item)?.clone())))
+            .collect()
     }
 
-    /// Filters by i64 timestamp being within the last N days from now.
+    /// Projects a float field min-max scaled to `[0.0, 1.0]`.
+    ///
+    /// Each value becomes `(v - min) / (max - min)` over the matching items, in
+    /// item order. When every value is equal the range is zero and all values
+    /// scale to `0.0`. `NaN` values are ignored for the range and stay `NaN`.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `days` - Number of days to look back
+    /// * `path` - The key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_last_days_timestamp(Event::created_at(), 30);
+    /// let features = query.select_scaled(House::area());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_last_days_timestamp(self, path: KeyPaths<T, i64>, days: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now - (days * 24 * 60 * 60 * 1000); // Convert days to milliseconds
-        self.where_after_timestamp(path, cutoff)
+    pub fn select_scaled(&self, path: KeyPaths<T, f64>) -> Vec<f64> {
+        let values = self.select(path);
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+
+        values
+            .into_iter()
+            .map(|v| if range > 0.0 { (v - min) / range } else if v.is_nan() { v } else { 0.0 })
+            .collect()
     }
 
-    /// Filters by i64 timestamp being within the next N days from now.
+    /// Labels each matching item with the value of the first arm whose predicate matches, like SQL `CASE`.
+    ///
+    /// Arms are tried in order and the first match wins; items matching no arm
+    /// get `default`. The result has one entry per matching item, in item order.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `days` - Number of days to look ahead
+    /// * `arms` - Pairs of predicate and the value to return when it matches
+    /// * `default` - The value for items matching no arm (the `ELSE` branch)
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let upcoming = query.where_next_days_timestamp(Event::scheduled_at(), 7);
+    /// // CASE WHEN price < 10 THEN 'budget' WHEN price < 100 THEN 'standard' ELSE 'premium' END
+    /// let tiers = query.select_case(
+    ///     vec![
+    ///         (Box::new(|p: &Product| p.price < 10.0), "budget"),
+    ///         (Box::new(|p: &Product| p.price < 100.0), "standard"),
+    ///     ],
+    ///     "premium",
+    /// );
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_next_days_timestamp(self, path: KeyPaths<T, i64>, days: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now + (days * 24 * 60 * 60 * 1000); // Convert days to milliseconds
-        self.where_before_timestamp(path, cutoff)
+    #[allow(clippy::type_complexity)]
+    pub fn select_case<R: Clone>(&self, arms: Vec<(Box<dyn Fn(&T) -> bool>, R)>, default: R) -> Vec<R> {
+        self.matching()
+            .map(|item| {
+                arms.iter()
+                    .find(|(predicate, _)| predicate(item))
+                    .map_or_else(|| default.clone(), |(_, value)| value.clone())
+            })
+            .collect()
     }
 
-    /// Filters by i64 timestamp being within the last N hours from now.
+    /// Selects the first present value among several optional fields, like SQL `COALESCE`.
+    ///
+    /// Paths are tried in order for each matching item; the result is `None`
+    /// only when every path yields `None`. Key-paths derived for `Option<F>`
+    /// fields already yield `None` for absent values, so they can be passed
+    /// directly.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `hours` - Number of hours to look back
+    /// * `paths` - Key-paths to the fields, in order of preference
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_last_hours_timestamp(Event::created_at(), 24);
+    /// // COALESCE(mobile, home)
+    /// let phones = query.select_coalesce(vec![Contact::mobile(), Contact::home()]);
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_last_hours_timestamp(self, path: KeyPaths<T, i64>, hours: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now - (hours * 60 * 60 * 1000); // Convert hours to milliseconds
-        self.where_after_timestamp(path, cutoff)
+    pub fn select_coalesce<F: Clone + 'static>(&self, paths: Vec<KeyPaths<T, F>>) -> Vec<Option<F>> {
+        self.matching()
+            .map(|item| paths.iter().find_map(|path| path.get(item).cloned()))
+            .collect()
     }
 
-    /// Filters by i64 timestamp being within the next N hours from now.
+    /// Projects matching items into named numeric columns, the layout columnar
+    /// engines such as Arrow or Polars ingest.
+    ///
+    /// Every column has one entry per matching item, in match order, so row
+    /// `i` of each column describes the same item. A missing value becomes
+    /// `f64::NAN` to keep the columns aligned. If a name is repeated, the last
+    /// spec with that name wins.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `hours` - Number of hours to look ahead
+    /// * `specs` - Pairs of column name and key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let upcoming = query.where_next_hours_timestamp(Event::scheduled_at(), 2);
+    /// let columns = query.select_columns(&[("price", Product::price()), ("weight", Product::weight())]);
+    /// let df = DataFrame::new(columns.into_iter().map(|(name, values)| Series::new(name, values)).collect())?;
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_next_hours_timestamp(self, path: KeyPaths<T, i64>, hours: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now + (hours * 60 * 60 * 1000); // Convert hours to milliseconds
-        self.where_before_timestamp(path, cutoff)
+    pub fn select_columns<'b>(&self, specs: &[(&'b str, KeyPaths<T, f64>)]) -> HashMap<&'b str, Vec<f64>> {
+        let mut columns: Vec<Vec<f64>> = vec![Vec::new(); specs.len()];
+        for item in self.matching() {
+            for (column, (_, path)) in columns.iter_mut().zip(specs) {
+                column.push(path.get(item).copied().unwrap_or(f64::NAN));
+            }
+        }
+
+        specs.iter().map(|(name, _)| *name).zip(columns).collect()
     }
 
-    /// Filters by i64 timestamp being within the last N minutes from now.
+    /// Projects matching items into JSON objects holding only the named fields.
+    ///
+    /// Each object has exactly the given keys, in match order of the items; a
+    /// field whose value is missing is emitted as `null`. Build the extractors
+    /// with `record_field`, or use the `select_records!` macro.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `minutes` - Number of minutes to look back
+    /// * `fields` - Pairs of output key and value extractor
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let recent = query.where_last_minutes_timestamp(Event::created_at(), 60);
+    /// let rows = query.select_records(&[
+    ///     ("id", record_field(Product::id())),
+    ///     ("name", record_field(Product::name())),
+    /// ]);
+    /// // [{"id": 1, "name": "Laptop"}, ...]
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_last_minutes_timestamp(self, path: KeyPaths<T, i64>, minutes: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now - (minutes * 60 * 1000); // Convert minutes to milliseconds
-        self.where_after_timestamp(path, cutoff)
+    #[cfg(feature = "serde")]
+    #[allow(clippy::type_complexity)]
+    pub fn select_records(
+        &self,
+        fields: &[(&str, Box<dyn Fn(&T) -> serde_json::Value>)],
+    ) -> Vec<serde_json::Map<String, serde_json::Value>> {
+        self.matching()
+            .map(|item| {
+                fields
+                    .iter()
+                    .map(|(name, extract)| (name.to_string(), extract(item)))
+                    .collect()
+            })
+            .collect()
     }
 
-    /// Filters by i64 timestamp being within the next N minutes from now.
+    /// Computes the sum of a numeric field.
+    ///
+    /// Returns `F::default()` (e.g. `0`) if no items match; use `sum_opt` to
+    /// distinguish an empty result from a zero sum.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
-    /// * `minutes` - Number of minutes to look ahead
+    /// * `path` - The key-path to the numeric field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let upcoming = query.where_next_minutes_timestamp(Event::scheduled_at(), 30);
+    /// let total_price = query.sum(Product::price());
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn where_next_minutes_timestamp(self, path: KeyPaths<T, i64>, minutes: i64) -> Self {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now + (minutes * 60 * 1000); // Convert minutes to milliseconds
-        self.where_before_timestamp(path, cutoff)
+    pub fn sum<F>(&self, path: KeyPaths<T, F>) -> F
+    where
+        F: Clone + std::ops::Add<Output = F> + Default + 'static,
+    {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .fold(F::default(), |acc, val| acc + val)
     }
 
-    /// Orders results by i64 timestamp in ascending order (oldest first).
+    /// Computes the sum of a numeric field, or `None` if no items match.
+    ///
+    /// Unlike `sum`, an empty result is reported as `None` rather than
+    /// `F::default()`, matching the behavior of `avg`, `min` and `max`.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `path` - The key-path to the numeric field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by_timestamp(Event::created_at());
+    /// match query.sum_opt(Product::price()) {
+    ///     Some(total) => println!("Total: {}", total),
+    ///     None => println!("No matching products"),
+    /// }
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn order_by_timestamp(&self, path: KeyPaths<T, i64>) -> Vec<T> {
-        let mut results: Vec<T> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
-            .collect();
-
-        results.sort_by(|a, b| {
-            let a_val = path.get(a).cloned().unwrap_or(0);
-            let b_val = path.get(b).cloned().unwrap_or(0);
-            a_val.cmp(&b_val)
-        });
-        results
+    pub fn sum_opt<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    where
+        F: Clone + std::ops::Add<Output = F> + 'static,
+    {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .reduce(|acc, val| acc + val)
     }
 
-    /// Orders results by i64 timestamp in descending order (newest first).
+    /// Computes the average of a float field.
+    ///
+    /// Returns `None` if no items match.
     ///
     /// # Arguments
     ///
-    /// * `path` - The key-path to the i64 timestamp field
+    /// * `path` - The key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sorted = query.order_by_timestamp_desc(Event::created_at());
+    /// let avg_price = query.avg(Product::price()).unwrap_or(0.0);
     /// ```
-    #[cfg(feature = "datetime")]
-    pub fn order_by_timestamp_desc(&self, path: KeyPaths<T, i64>) -> Vec<T> {
-        let mut results: Vec<T> = self
-            .data
-            .iter()
-            .filter(|item| self.filters.iter().all(|f| f(item)))
-            .cloned()
+    pub fn avg(&self, path: KeyPaths<T, f64>) -> Option<f64> {
+        let items: Vec<f64> = self.matching()
+            .filter_map(|item| path.get(item).cloned())
             .collect();
 
-        results.sort_by(|a, b| {
-            let a_val = path.get(a).cloned().unwrap_or(0);
-            let b_val = path.get(b).cloned().unwrap_or(0);
-            b_val.cmp(&a_val)
-        });
-        results
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.iter().sum::<f64>() / items.len() as f64)
+        }
     }
-}
-
-/// Helper struct for pagination after a skip operation.
-///
-/// Created by calling `skip()` on a `Query`.
-pub struct QueryWithSkip<'a, 'b, T: 'static> {
-    query: &'b Query<'a, T>,
-    offset: usize,
-}
 
-impl<'a, 'b, T: 'static> QueryWithSkip<'a, 'b, T> {
-    /// Returns up to `n` items after skipping the offset.
+    /// Computes the sums of several float fields in a single pass.
+    ///
+    /// Returns one sum per key-path, in the order given; each is the value
+    /// `sum(path)` would return, with missing values contributing nothing.
     ///
     /// # Arguments
     ///
-    /// * `n` - The maximum number of items to return
+    /// * `paths` - The key-paths to the f64 fields
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let page_2 = query.skip(20).limit(10);
+    /// let totals = query.sum_fields(&[Order::subtotal(), Order::tax(), Order::shipping()]);
     /// ```
-    pub fn limit(&self, n: usize) -> Vec<&'a T> {
-        self.query
-            .data
-            .iter()
-            .filter(|item| self.query.filters.iter().all(|f| f(item)))
-            .skip(self.offset)
-            .take(n)
-            .collect()
+    pub fn sum_fields(&self, paths: &[KeyPaths<T, f64>]) -> Vec<f64> {
+        let mut sums = vec![0.0; paths.len()];
+        for item in self.matching() {
+            for (sum, path) in sums.iter_mut().zip(paths) {
+                if let Some(value) = path.get(item) {
+                    *sum += value;
+                }
+            }
+        }
+        sums
     }
-}
 
-    // Parallel operations (only available with parallel feature)
-    #[cfg(feature = "parallel")]
-    impl<'a, T: 'static + Send + Sync> Query<'a, T> {
-    /// Get all items using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Computes the total of a `Duration` field.
     ///
-    /// # Example
+    /// Returns `Duration::ZERO` if no items match.
     ///
-    /// ```ignore
-    /// let results = query.all_parallel();
-    /// ```
-    pub fn all_parallel(&self) -> Vec<&'a T> {
-        use rayon::prelude::*;
-        self.data.par_iter().collect()
-    }
-
-    /// Count all items using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the `Duration` field
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total overflows `Duration`.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let count = query.count_parallel();
+    /// let total = query.sum_duration_std(Job::elapsed());
     /// ```
-    pub fn count_parallel(&self) -> usize {
-        use rayon::prelude::*;
-        self.data.par_iter().count()
+    pub fn sum_duration_std(&self, path: KeyPaths<T, Duration>) -> Duration {
+        self.matching()
+            .filter_map(|item| path.get(item).copied())
+            .fold(Duration::ZERO, |acc, val| acc + val)
     }
 
-    /// Check if any items exist using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Computes the average of a `Duration` field with nanosecond precision.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the `Duration` field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let exists = query.exists_parallel();
+    /// let mean = query.avg_duration_std(Job::elapsed());
     /// ```
-    pub fn exists_parallel(&self) -> bool {
-        use rayon::prelude::*;
-        self.data.par_iter().any(|_| true)
+    pub fn avg_duration_std(&self, path: KeyPaths<T, Duration>) -> Option<Duration> {
+        let (total, count) = self.matching()
+            .filter_map(|item| path.get(item).copied())
+            .fold((Duration::ZERO, 0u128), |(acc, n), val| (acc + val, n + 1));
+
+        if count == 0 {
+            return None;
+        }
+        let nanos = total.as_nanos() / count;
+        Some(Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        ))
     }
 
-    /// Find minimum value using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Finds the minimum value of a field.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let min = query.min_parallel(Product::price());
+    /// let min_stock = query.min(Product::stock());
     /// ```
-    pub fn min_parallel<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    pub fn min<F>(&self, path: KeyPaths<T, F>) -> Option<F>
     where
-        F: Ord + Clone + 'static + Send + Sync,
+        F: Ord + Clone + 'static,
     {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
+        self.matching()
             .filter_map(|item| path.get(item).cloned())
             .min()
     }
 
-    /// Find maximum value using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Finds the maximum value of a field.
     ///
-    /// # Example
+    /// Returns `None` if no items match.
     ///
-    /// ```ignore
-    /// let max = query.max_parallel(Product::price());
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let max_stock = query.max(Product::stock());
     /// ```
-    pub fn max_parallel<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    pub fn max<F>(&self, path: KeyPaths<T, F>) -> Option<F>
     where
-        F: Ord + Clone + 'static + Send + Sync,
+        F: Ord + Clone + 'static,
     {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
+        self.matching()
             .filter_map(|item| path.get(item).cloned())
             .max()
     }
 
-    /// Compute sum using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Finds the minimum value of a float field.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let sum = query.sum_parallel(Product::price());
+    /// let min_price = query.min_float(Product::price());
     /// ```
-    pub fn sum_parallel<F>(&self, path: KeyPaths<T, F>) -> F
-    where
-        F: Clone + std::ops::Add<Output = F> + Default + 'static + Send + Sync + std::iter::Sum,
-    {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
+    pub fn min_float(&self, path: KeyPaths<T, f64>) -> Option<f64> {
+        self.matching()
             .filter_map(|item| path.get(item).cloned())
-            .sum()
+            .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
     }
 
-    /// Compute average using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Finds the maximum value of a float field.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let avg = query.avg_parallel(Product::price());
+    /// let max_price = query.max_float(Product::price());
     /// ```
-    pub fn avg_parallel(&self, path: KeyPaths<T, f64>) -> Option<f64> {
-        use rayon::prelude::*;
-        let items: Vec<f64> = self.data
-            .par_iter()
+    pub fn max_float(&self, path: KeyPaths<T, f64>) -> Option<f64> {
+        self.matching()
             .filter_map(|item| path.get(item).cloned())
-            .collect();
+            .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    }
 
-        if items.is_empty() {
-            None
-        } else {
-            Some(items.par_iter().sum::<f64>() / items.len() as f64)
+    /// Returns the position in the source slice of the matching item with the largest value.
+    ///
+    /// Ties keep the first such item. Items whose key-path yields no value
+    /// are skipped. Because the index refers to the original slice, callers
+    /// can use it to update that exact element afterwards.
+    ///
+    /// Returns `None` if no matching item has a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field being maximized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(i) = Query::new(&products).argmax(Product::price()) {
+    ///     products[i].featured = true;
+    /// }
+    /// ```
+    pub fn argmax<F>(&self, path: KeyPaths<T, F>) -> Option<usize>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.extreme_index(path, |candidate, current| candidate > current)
+    }
+
+    /// Returns the position in the source slice of the matching item with the smallest value.
+    ///
+    /// Ties keep the first such item; items whose key-path yields no value
+    /// are skipped. Returns `None` if no matching item has a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field being minimized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cheapest = Query::new(&products).argmin(Product::price());
+    /// ```
+    pub fn argmin<F>(&self, path: KeyPaths<T, F>) -> Option<usize>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.extreme_index(path, |candidate, current| candidate < current)
+    }
+
+    fn extreme_index<F>(&self, path: KeyPaths<T, F>, replaces: impl Fn(&F, &F) -> bool) -> Option<usize>
+    where
+        F: PartialOrd + 'static,
+    {
+        let mut best: Option<(usize, &F)> = None;
+
+        for (index, item) in self.data.iter().enumerate() {
+            if !self.matches_at(index, item) {
+                continue;
+            }
+            let Some(candidate) = path.get(item) else {
+                continue;
+            };
+            match best {
+                Some((_, current)) if !replaces(candidate, current) => {}
+                _ => best = Some((index, candidate)),
+            }
         }
+
+        best.map(|(index, _)| index)
     }
 
-    /// Find minimum i64 timestamp using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Computes count, sum, min, max, mean and standard deviation of a float field in one pass.
+    ///
+    /// The standard deviation is the sample standard deviation (divisor
+    /// `count - 1`, as in pandas), computed with Welford's algorithm; it is
+    /// `0.0` when only one value is present. `NaN` values are ignored.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let earliest = query.min_timestamp_parallel(Event::created_at());
+    /// if let Some(stats) = query.describe(Product::price()) {
+    ///     println!("{} items, mean {:.2} ± {:.2}", stats.count, stats.mean, stats.stddev);
+    /// }
     /// ```
-    pub fn min_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
-            .filter_map(|item| path.get(item).cloned())
-            .min()
+    pub fn describe(&self, path: KeyPaths<T, f64>) -> Option<Describe> {
+        describe_values(self.matching().filter_map(|item| path.get(item).copied()))
     }
 
-    /// Find maximum i64 timestamp using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Returns matching items whose field lies within `k` standard deviations
+    /// of the mean.
+    ///
+    /// The filters run once: matching values are gathered in a single pass,
+    /// their mean and sample standard deviation (as in `describe`) computed
+    /// from them, and items within `mean ± k * stddev` kept. Items whose field
+    /// is missing or NaN are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
+    /// * `k` - The number of standard deviations to allow
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let latest = query.max_timestamp_parallel(Event::created_at());
+    /// let cleaned = query.where_within_stddev(Reading::value(), 3.0);
     /// ```
-    pub fn max_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
-            .filter_map(|item| path.get(item).cloned())
-            .max()
+    pub fn where_within_stddev(&self, path: KeyPaths<T, f64>, k: f64) -> Vec<&'a T> {
+        let values: Vec<(&'a T, f64)> = self
+            .all()
+            .into_iter()
+            .filter_map(|item| path.get(item).map(|&v| (item, v)))
+            .collect();
+        let Some(Describe { mean, stddev, .. }) = describe_values(values.iter().map(|&(_, v)| v)) else {
+            return Vec::new();
+        };
+        // NaN values fail the comparison below, so they are dropped too.
+        let bound = k * stddev;
+
+        values
+            .into_iter()
+            .filter(|(_, v)| (v - mean).abs() <= bound)
+            .map(|(item, _)| item)
+            .collect()
     }
 
-    /// Compute average i64 timestamp using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Computes a percentile of a float field using linear interpolation.
+    ///
+    /// Shorthand for `percentile_with(path, p, PercentileMethod::Linear)`.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let avg = query.avg_timestamp_parallel(Event::created_at());
+    /// let p95 = query.percentile(Request::latency_ms(), 95.0);
     /// ```
-    pub fn avg_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
-        use rayon::prelude::*;
-        let items: Vec<i64> = self.data
-            .par_iter()
+    pub fn percentile(&self, path: KeyPaths<T, f64>, p: f64) -> Option<f64> {
+        self.percentile_with(path, p, PercentileMethod::Linear)
+    }
+
+    /// Computes a percentile of a float field using an explicit method.
+    ///
+    /// See `PercentileMethod` for the formula each method uses. `NaN` values are ignored.
+    ///
+    /// Returns `None` if no items match or `p` is outside `0.0..=100.0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
+    /// * `p` - The percentile, from `0.0` to `100.0`
+    /// * `method` - How to pick or interpolate between ranked values
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let median = query.percentile_with(Product::price(), 50.0, PercentileMethod::NearestRank);
+    /// ```
+    pub fn percentile_with(&self, path: KeyPaths<T, f64>, p: f64, method: PercentileMethod) -> Option<f64> {
+        if !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+
+        let mut values: Vec<f64> = self.matching()
             .filter_map(|item| path.get(item).cloned())
+            .filter(|v| !v.is_nan())
             .collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
 
-        if items.is_empty() {
-            None
-        } else {
-            Some(items.par_iter().sum::<i64>() / items.len() as i64)
+        Some(percentile_of_sorted(&values, p, method))
+    }
+
+    /// Computes a percentile of a float field within each group, using linear interpolation.
+    ///
+    /// Values are grouped by `key`, then each group is sorted and interpolated
+    /// as in `percentile`. `NaN` values are ignored, and groups left without
+    /// values are omitted.
+    ///
+    /// Returns an empty map if `p` is outside `0.0..=100.0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path to group by
+    /// * `value` - The key-path to the f64 field
+    /// * `p` - The percentile, from `0.0` to `100.0`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // p95 latency per endpoint
+    /// let p95 = query.percentile_by_group(Request::endpoint(), Request::latency_ms(), 95.0);
+    /// ```
+    pub fn percentile_by_group<K>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, f64>, p: f64) -> HashMap<K, f64>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        if !(0.0..=100.0).contains(&p) {
+            return HashMap::new();
+        }
+
+        let mut groups: HashMap<K, Vec<f64>> = HashMap::new();
+        for item in self.matching() {
+            let (Some(group), Some(&v)) = (key.get(item), value.get(item)) else {
+                continue;
+            };
+            if !v.is_nan() {
+                groups.entry(group.clone()).or_default().push(v);
+            }
         }
+
+        groups
+            .into_iter()
+            .map(|(group, mut values)| {
+                values.sort_by(f64::total_cmp);
+                (group, percentile_of_sorted(&values, p, PercentileMethod::Linear))
+            })
+            .collect()
     }
 
-    /// Compute sum of i64 timestamps using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Checks if any items match the query filters.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let total = query.sum_timestamp_parallel(Event::created_at());
+    /// let has_results = query.exists();
     /// ```
-    pub fn sum_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> i64 {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
-            .filter_map(|item| path.get(item).cloned())
-            .sum()
+    pub fn exists(&self) -> bool {
+        self.matching().next().is_some()
     }
 
-    /// Count i64 timestamps using parallel processing.
-    /// Note: This method ignores filters for thread safety.
+    /// Checks if no items match the query filters.
+    ///
+    /// This is the inverse of `exists` and stops at the first match.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let count = query.count_timestamp_parallel(Event::created_at());
+    /// if query.is_empty_result() {
+    ///     println!("No results");
+    /// }
     /// ```
-    pub fn count_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> usize {
-        use rayon::prelude::*;
-        self.data
-            .par_iter()
-            .filter(|item| path.get(item).is_some())
+    pub fn is_empty_result(&self) -> bool {
+        !self.exists()
+    }
+
+    /// Counts matching items that also satisfy an extra key-path predicate (terminal).
+    ///
+    /// Like `count()` but with a field-specific predicate; the query itself is
+    /// left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let expensive = query.count_where(Product::price(), |&p| p > 500.0);
+    /// ```
+    pub fn count_where<F>(&self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool) -> usize
+    where
+        F: 'static,
+    {
+        self.matching()
+            .filter(|item| path.get(item).is_some_and(&predicate))
             .count()
     }
-}
 
+    /// Checks if any matching item also satisfies an extra key-path predicate (terminal).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let has_out_of_stock = query.any_where(Product::stock(), |&s| s == 0);
+    /// ```
+    pub fn any_where<F>(&self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool) -> bool
+    where
+        F: 'static,
+    {
+        self.matching()
+            .any(|item| path.get(item).is_some_and(&predicate))
+    }
+
+    /// Returns the first matching item that also satisfies an extra key-path predicate (terminal).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first_sale = query.first_where(Product::discount(), |&d| d > 0.0);
+    /// ```
+    pub fn first_where<F>(&self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool) -> Option<&'a T>
+    where
+        F: 'static,
+    {
+        self.matching()
+            .find(|item| path.get(item).is_some_and(&predicate))
+    }
+
+    /// Checks if every matching item satisfies an extra key-path predicate (terminal).
+    ///
+    /// Stops at the first violating item, so neither the remaining rows nor
+    /// `predicate` are evaluated past it. Items whose key-path yields no value
+    /// count as violations; an empty result is vacuously `true`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let all_priced = query.all_match(Product::price(), |&p| p > 0.0);
+    /// ```
+    pub fn all_match<F>(&self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool) -> bool
+    where
+        F: 'static,
+    {
+        self.matching()
+            .all(|item| path.get(item).is_some_and(&predicate))
+    }
+
+    /// Asserts that every matching item satisfies a key-path predicate.
+    ///
+    /// Intended for tests: on failure the panic message includes `msg` and
+    /// the first offending item, so the broken invariant is easy to track
+    /// down. Items whose key-path yields no value count as violations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any matching item does not satisfy `predicate`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Query::new(&imported)
+    ///     .where_(Product::active(), |&a| a)
+    ///     .assert_all(Product::price(), |&p| p > 0.0, "active products must have a price");
+    /// ```
+    #[track_caller]
+    pub fn assert_all<F>(&self, path: KeyPaths<T, F>, predicate: impl Fn(&F) -> bool, msg: &str)
+    where
+        T: std::fmt::Debug,
+        F: 'static,
+    {
+        let offender = self.matching()
+            .enumerate()
+            .find(|(_, item)| !path.get(item).is_some_and(&predicate));
+
+        if let Some((position, item)) = offender {
+            panic!("{}: matching item #{} violates the predicate: {:?}", msg, position, item);
+        }
+    }
+
+    /// Returns items matched by both this query and `other` (AND / intersection).
+    ///
+    /// Both queries must wrap the same slice, so results are compared by
+    /// identity rather than by value. Results keep the slice order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` was built over a different slice.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let in_stock = Query::new(&products).where_(Product::stock(), |&s| s > 0);
+    /// let on_sale = Query::new(&products).where_(Product::discount(), |&d| d > 0.0);
+    /// let deals = in_stock.and_also(&on_sale);
+    /// ```
+    pub fn and_also(&self, other: &Query<'a, T>) -> Vec<&'a T> {
+        self.assert_same_source(other);
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, item)| self.matches_at(*index, item) && other.matches_at(*index, item))
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// Returns items matched by this query or `other` (OR / union).
+    ///
+    /// Both queries must wrap the same slice, so results are compared by
+    /// identity rather than by value. Each item appears at most once, in slice order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` was built over a different slice.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cheap = Query::new(&products).where_(Product::price(), |&p| p < 10.0);
+    /// let featured = Query::new(&products).where_(Product::featured(), |&f| f);
+    /// let highlighted = cheap.or_else(&featured);
+    /// ```
+    pub fn or_else(&self, other: &Query<'a, T>) -> Vec<&'a T> {
+        self.assert_same_source(other);
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, item)| self.matches_at(*index, item) || other.matches_at(*index, item))
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    fn assert_same_source(&self, other: &Query<'a, T>) {
+        assert!(
+            std::ptr::eq(self.data, other.data),
+            "combined queries must wrap the same slice"
+        );
+    }
+
+    /// Evaluates the filters once into a mask with one flag per item in the data.
+    ///
+    /// The mask can be cached, combined with `QueryPlan`, and turned back into
+    /// a query with `Query::from_mask`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let in_stock = Query::new(&products).where_(Product::stock(), |&s| s > 0).mask();
+    /// ```
+    pub fn mask(&self) -> Vec<bool> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, item)| self.matches_at(index, item))
+            .collect()
+    }
+
+    /// Returns the positions in the source slice of the items matching the query filters.
+    ///
+    /// Useful for correlating results with the underlying storage, e.g. to
+    /// update matched items in place through a mutable borrow taken later.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let stale = Query::new(&products).where_(Product::stock(), |&s| s == 0).indices();
+    /// for i in stale {
+    ///     products[i].active = false;
+    /// }
+    /// ```
+    pub fn indices(&self) -> Vec<usize> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, item)| self.matches_at(*index, item))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Splits the data into matching items and rejected items with reasons.
+    ///
+    /// Each rejected item is paired with the label of the first filter it
+    /// failed, in the order the filters were added. Filters added without a
+    /// label (anything other than `where_labeled`) report `"unlabeled"`, and
+    /// items excluded by a `from_mask` mask report `"masked"`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (clean, rejected) = Query::new(&records)
+    ///     .where_labeled("missing email", Record::email(), |e| !e.is_empty())
+    ///     .where_labeled("negative amount", Record::amount(), |&a| a >= 0.0)
+    ///     .partition_explained();
+    /// for (record, reason) in rejected {
+    ///     println!("{}: {}", record.id, reason);
+    /// }
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn partition_explained(&self) -> (Vec<&'a T>, Vec<(&'a T, &'static str)>) {
+        let mut passed = Vec::new();
+        let mut rejected = Vec::new();
+        for (position, item) in self.data.iter().enumerate() {
+            if self.mask.as_ref().is_some_and(|mask| !mask[position]) {
+                rejected.push((item, "masked"));
+                continue;
+            }
+            match self.filters.iter().position(|f| !f(item)) {
+                None => passed.push(item),
+                Some(index) => {
+                    let label = self.labels.get(&index).copied().unwrap_or("unlabeled");
+                    rejected.push((item, label));
+                }
+            }
+        }
+        (passed, rejected)
+    }
+
+    /// Returns the length of the underlying data, ignoring filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// println!("{} of {} results", query.count(), query.total());
+    /// ```
+    pub fn total(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if the underlying data is empty, ignoring filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if query.is_empty_source() {
+    ///     println!("No data loaded");
+    /// }
+    /// ```
+    pub fn is_empty_source(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Counts matching items per fixed-size time window.
+    ///
+    /// Each item is placed in the window `[start, start + window_ms)` containing its
+    /// timestamp. The map is keyed by window start and iterates in ascending order;
+    /// windows without any items are not included. A window that would start
+    /// below `i64::MIN` is keyed by `i64::MIN`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ts` - The key-path to the i64 timestamp field (milliseconds)
+    /// * `window_ms` - The window size in milliseconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_ms` is not positive.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Requests per minute
+    /// let per_minute = query.count_per_window(Request::timestamp(), 60_000);
+    /// ```
+    pub fn count_per_window(&self, ts: KeyPaths<T, i64>, window_ms: i64) -> BTreeMap<i64, usize> {
+        assert!(window_ms > 0, "window_ms must be positive, got {}", window_ms);
+
+        let mut windows = BTreeMap::new();
+        for item in self.matching() {
+            if let Some(&timestamp) = ts.get(item) {
+                let start = timestamp.saturating_sub(timestamp.rem_euclid(window_ms));
+                *windows.entry(start).or_insert(0) += 1;
+            }
+        }
+
+        windows
+    }
+
+    /// Groups matching items into numeric ranges defined by bin edges.
+    ///
+    /// For `n` edges `b[0] < b[1] < ... < b[n-1]` there are `n + 1` bins:
+    ///
+    /// * bin `0` is the underflow bin, `v < b[0]`
+    /// * bin `i` for `1 <= i < n` is `b[i-1] <= v < b[i]`
+    /// * bin `n` is the overflow bin, `v >= b[n-1]`
+    ///
+    /// A value equal to an edge goes to the bin above it. Bins without items are
+    /// not included, and `NaN` values are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field
+    /// * `bounds` - The bin edges, in ascending order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bounds` is not sorted in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Price bands: <10, 10-50, 50-100, >=100
+    /// let bands = query.group_by_ranges(Product::price(), &[10.0, 50.0, 100.0]);
+    /// let mid_range = bands.get(&2).map_or(0, |items| items.len());
+    /// ```
+    pub fn group_by_ranges(&self, path: KeyPaths<T, f64>, bounds: &[f64]) -> BTreeMap<usize, Vec<&'a T>> {
+        assert!(
+            bounds.windows(2).all(|pair| pair[0] < pair[1]),
+            "bounds must be sorted in ascending order"
+        );
+
+        let mut bins: BTreeMap<usize, Vec<&'a T>> = BTreeMap::new();
+        for item in self.matching() {
+            if let Some(&value) = path.get(item) {
+                if value.is_nan() {
+                    continue;
+                }
+                let bin = bounds.partition_point(|&edge| edge <= value);
+                bins.entry(bin).or_default().push(item);
+            }
+        }
+
+        bins
+    }
+
+    /// Builds a pivot table summing `value` over every `row` × `col` pair.
+    ///
+    /// Only combinations that occur in the matching items are present, so a
+    /// missing cell means no items rather than a zero total. Items missing
+    /// any of the three fields are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The key-path to the row label
+    /// * `col` - The key-path to the column label
+    /// * `value` - The key-path to the value being summed
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Total sales per region and product
+    /// let table = Query::new(&sales).pivot(Sale::region(), Sale::product(), Sale::amount());
+    /// let north_laptops = table["north"]["laptop"];
+    /// ```
+    pub fn pivot<R, C>(&self, row: KeyPaths<T, R>, col: KeyPaths<T, C>, value: KeyPaths<T, f64>) -> HashMap<R, HashMap<C, f64>>
+    where
+        R: Eq + std::hash::Hash + Clone + 'static,
+        C: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut table: HashMap<R, HashMap<C, f64>> = HashMap::new();
+
+        for item in self.matching() {
+            let (Some(r), Some(c), Some(v)) = (row.get(item), col.get(item), value.get(item)) else {
+                continue;
+            };
+            *table.entry(r.clone()).or_default().entry(c.clone()).or_default() += v;
+        }
+
+        table
+    }
+
+    /// Finds keys shared by more than one matching item.
+    ///
+    /// Useful for checking that a field expected to be unique really is. Keys
+    /// that occur once are omitted; each duplicated key lists every offending
+    /// item in data order.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field that should be unique
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let clashes = Query::new(&imported_users).find_duplicates(User::email());
+    /// for (email, users) in &clashes {
+    ///     eprintln!("{} is used by {} records", email, users.len());
+    /// }
+    /// ```
+    pub fn find_duplicates<F>(&self, path: KeyPaths<T, F>) -> HashMap<F, Vec<&'a T>>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: HashMap<F, Vec<&'a T>> = HashMap::new();
+        for item in self.matching() {
+            if let Some(key) = path.get(item).cloned() {
+                groups.entry(key).or_default().push(item);
+            }
+        }
+
+        groups.retain(|_, items| items.len() > 1);
+        groups
+    }
+
+    /// Keeps the first `n` matching items of each group, in source order.
+    ///
+    /// No sorting is done, so this suits data that is already ordered (for
+    /// example events by time). Groups keep at most `n` items; with `n == 0`
+    /// the result is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path to group by
+    /// * `n` - The maximum number of items per group
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Latest 5 events per user, with events stored newest first
+    /// let recent = query.take_n_per_group(Event::user_id(), 5);
+    /// ```
+    pub fn take_n_per_group<K>(&self, key: KeyPaths<T, K>, n: usize) -> HashMap<K, Vec<&'a T>>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: HashMap<K, Vec<&'a T>> = HashMap::new();
+        if n == 0 {
+            return groups;
+        }
+
+        for item in self.matching() {
+            if let Some(group) = key.get(item) {
+                match groups.get_mut(group) {
+                    Some(items) if items.len() >= n => {}
+                    Some(items) => items.push(item),
+                    None => {
+                        groups.insert(group.clone(), vec![item]);
+                    }
+                }
+            }
+        }
+
+        groups
+    }
+
+    // DateTime operations for SystemTime
+    /// Filter by SystemTime being after a reference time.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `reference` - The reference time to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_after_systemtime(Event::timestamp(), &cutoff_time);
+    /// ```
+    pub fn where_after_systemtime(self, path: KeyPaths<T, SystemTime>, reference: SystemTime) -> Self {
+        self.where_(path, move |time| time > &reference)
+    }
+
+    /// Filter by SystemTime being before a reference time.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `reference` - The reference time to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let old = query.where_before_systemtime(Event::timestamp(), &cutoff_time);
+    /// ```
+    pub fn where_before_systemtime(self, path: KeyPaths<T, SystemTime>, reference: SystemTime) -> Self {
+        self.where_(path, move |time| time < &reference)
+    }
+
+    /// Filter by SystemTime being between two times (inclusive).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `start` - The start time
+    /// * `end` - The end time
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let range = query.where_between_systemtime(Event::timestamp(), &start, &end);
+    /// ```
+    pub fn where_between_systemtime(
+        self,
+        path: KeyPaths<T, SystemTime>,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Self {
+        self.where_(path, move |time| time >= &start && time <= &end)
+    }
+
+    /// Filter by SystemTime being within a duration before now.
+    ///
+    /// Keeps times after `SystemTime::now() - window`; like the i64 helpers,
+    /// there is no upper bound, so future times also match. If the cutoff
+    /// would precede the earliest representable time, every item matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `window` - How far back from now to look
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_within_systemtime(Event::timestamp(), Duration::from_secs(15 * 60));
+    /// ```
+    pub fn where_within_systemtime(self, path: KeyPaths<T, SystemTime>, window: Duration) -> Self {
+        let cutoff = SystemTime::now().checked_sub(window);
+        self.where_(path, move |time| match cutoff {
+            Some(cutoff) => *time > cutoff,
+            None => true,
+        })
+    }
+
+    /// Filter by SystemTime being within the last N days from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `days` - Number of days to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_last_days_systemtime(Event::timestamp(), 7);
+    /// ```
+    pub fn where_last_days_systemtime(self, path: KeyPaths<T, SystemTime>, days: u64) -> Self {
+        self.where_within_systemtime(path, Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+    }
+
+    /// Filter by SystemTime being within the last N hours from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the SystemTime field
+    /// * `hours` - Number of hours to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_last_hours_systemtime(Event::timestamp(), 24);
+    /// ```
+    pub fn where_last_hours_systemtime(self, path: KeyPaths<T, SystemTime>, hours: u64) -> Self {
+        self.where_within_systemtime(path, Duration::from_secs(hours.saturating_mul(60 * 60)))
+    }
+}
+
+// DateTime operations with chrono (only available with datetime feature)
+#[cfg(feature = "datetime")]
+impl<'a, T: 'static> Query<'a, T> {
+    /// Filter by DateTime being after a reference time.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `reference` - The reference time to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_after(Event::timestamp(), &cutoff_time);
+    /// ```
+    pub fn where_after<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, reference: DateTime<Tz>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        self.where_(path, move |time| time > &reference)
+    }
+
+    /// Filter by DateTime being before a reference time.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `reference` - The reference time to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let old = query.where_before(Event::timestamp(), &cutoff_time);
+    /// ```
+    pub fn where_before<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, reference: DateTime<Tz>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        self.where_(path, move |time| time < &reference)
+    }
+
+    /// Filter by DateTime being between two times (inclusive).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `start` - The start time
+    /// * `end` - The end time
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let range = query.where_between(Event::timestamp(), &start, &end);
+    /// ```
+    pub fn where_between<Tz>(
+        self,
+        path: KeyPaths<T, DateTime<Tz>>,
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+    ) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        self.where_(path, move |time| time >= &start && time <= &end)
+    }
+
+    /// Filter by DateTime being today.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `now` - The current DateTime to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let today = query.where_today(Event::timestamp(), &Utc::now());
+    /// ```
+    pub fn where_today<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, now: DateTime<Tz>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        self.where_(path, move |time| {
+            time.date_naive() == now.date_naive()
+        })
+    }
+
+    /// Filter by DateTime year.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `year` - The year to filter by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let this_year = query.where_year(Event::timestamp(), 2024);
+    /// ```
+    pub fn where_year<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, year: i32) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Datelike;
+        self.where_(path, move |time| time.year() == year)
+    }
+
+    /// Filter by DateTime month.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `month` - The month to filter by (1-12)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let december = query.where_month(Event::timestamp(), 12);
+    /// ```
+    pub fn where_month<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, month: u32) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Datelike;
+        self.where_(path, move |time| time.month() == month)
+    }
+
+    /// Filter by DateTime day.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    /// * `day` - The day to filter by (1-31)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first = query.where_day(Event::timestamp(), 1);
+    /// ```
+    pub fn where_day<Tz>(self, path: KeyPaths<T, DateTime<Tz>>, day: u32) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Datelike;
+        self.where_(path, move |time| time.day() == day)
+    }
+
+    /// Filter by weekend dates (Saturday and Sunday).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let weekend_events = query.where_weekend(Event::timestamp());
+    /// ```
+    pub fn where_weekend<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Datelike;
+        self.where_(path, |time| {
+            let weekday = time.weekday().num_days_from_monday();
+            weekday >= 5
+        })
+    }
+
+    /// Filter by weekday dates (Monday through Friday).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let weekday_events = query.where_weekday(Event::timestamp());
+    /// ```
+    pub fn where_weekday<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Datelike;
+        self.where_(path, |time| {
+            let weekday = time.weekday().num_days_from_monday();
+            weekday < 5
+        })
+    }
+
+    /// Filter by business hours (9 AM - 5 PM).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let business_hours = query.where_business_hours(Event::timestamp());
+    /// ```
+    pub fn where_business_hours<Tz>(self, path: KeyPaths<T, DateTime<Tz>>) -> Self
+    where
+        Tz: TimeZone + 'static,
+        Tz::Offset: std::fmt::Display,
+    {
+        use chrono::Timelike;
+        self.where_(path, |time| {
+            let hour = time.hour();
+            hour >= 9 && hour < 17
+        })
+    }
+
+    /// Counts matching items per hour of the day, ignoring the date.
+    ///
+    /// Index `h` of the result holds the number of items whose time falls in
+    /// hour `h` (0-23), read in the timestamp's own time zone. Useful for
+    /// finding the busiest hours across many days.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the DateTime field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_hour = query.group_by_hour_of_day(Request::received_at());
+    /// let busiest = (0..24).max_by_key(|&h| by_hour[h]);
+    /// ```
+    pub fn group_by_hour_of_day<Tz>(&self, path: KeyPaths<T, DateTime<Tz>>) -> [usize; 24]
+    where
+        Tz: TimeZone + 'static,
+    {
+        use chrono::Timelike;
+        let mut buckets = [0; 24];
+        for item in self.matching() {
+            if let Some(time) = path.get(item) {
+                buckets[time.hour() as usize] += 1;
+            }
+        }
+        buckets
+    }
+}
+
+// Operations that require Clone - separated for flexibility
+impl<'a, T: 'static + Clone> Query<'a, T> {
+    /// Orders results by a field in ascending order.
+    /// 
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to order by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by(Product::name());
+    /// ```
+    pub fn order_by<F>(&self, path: KeyPaths<T, F>) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by_key(|item| path.get(item).cloned());
+        results
+    }
+
+    /// Orders results by a field in descending order.
+    /// 
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to order by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by_desc(Product::stock());
+    /// ```
+    pub fn order_by_desc<F>(&self, path: KeyPaths<T, F>) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            let a_val = path.get(a).cloned();
+            let b_val = path.get(b).cloned();
+            b_val.cmp(&a_val)
+        });
+        results
+    }
+
+    /// Orders results by a field in ascending order, breaking ties by a secondary key.
+    ///
+    /// Use a unique key such as an id as the tie-break so records that share
+    /// the primary value always come out in the same order.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - The key-path to the field to order by
+    /// * `id` - The key-path used to order items with equal primary values
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // SQL: ORDER BY status ASC, id ASC
+    /// let sorted = query.order_by_then_id(Order::status(), Order::id());
+    /// ```
+    pub fn order_by_then_id<F, K>(&self, primary: KeyPaths<T, F>, id: KeyPaths<T, K>) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            primary
+                .get(a)
+                .cmp(&primary.get(b))
+                .then_with(|| id.get(a).cmp(&id.get(b)))
+        });
+        results
+    }
+
+    /// Orders results by a field in descending order, breaking ties by a secondary key in ascending order.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - The key-path to the field to order by
+    /// * `id` - The key-path used to order items with equal primary values
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // SQL: ORDER BY priority DESC, id ASC
+    /// let sorted = query.order_by_desc_then_id(Ticket::priority(), Ticket::id());
+    /// ```
+    pub fn order_by_desc_then_id<F, K>(&self, primary: KeyPaths<T, F>, id: KeyPaths<T, K>) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            primary
+                .get(b)
+                .cmp(&primary.get(a))
+                .then_with(|| id.get(a).cmp(&id.get(b)))
+        });
+        results
+    }
+
+    /// Orders results by a field in ascending order, placing items without a value first or last.
+    ///
+    /// Items whose key-path yields no value (e.g. a `None` optional field) are
+    /// treated as nulls. Ties keep their original order.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to order by
+    /// * `nulls` - Where to place items without a value
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // SQL: ORDER BY discount ASC NULLS LAST
+    /// let sorted = query.order_by_nulls(Product::discount(), NullsOrder::Last);
+    /// ```
+    pub fn order_by_nulls<F>(&self, path: KeyPaths<T, F>, nulls: NullsOrder) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        self.sorted_with_nulls(path, nulls, false)
+    }
+
+    /// Orders results by a field in descending order, placing items without a value first or last.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to order by
+    /// * `nulls` - Where to place items without a value
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // SQL: ORDER BY discount DESC NULLS FIRST
+    /// let sorted = query.order_by_nulls_desc(Product::discount(), NullsOrder::First);
+    /// ```
+    pub fn order_by_nulls_desc<F>(&self, path: KeyPaths<T, F>, nulls: NullsOrder) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        self.sorted_with_nulls(path, nulls, true)
+    }
+
+    fn sorted_with_nulls<F>(&self, path: KeyPaths<T, F>, nulls: NullsOrder, descending: bool) -> Vec<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| match (path.get(a), path.get(b)) {
+            (Some(a_val), Some(b_val)) if descending => b_val.cmp(a_val),
+            (Some(a_val), Some(b_val)) => a_val.cmp(b_val),
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => match nulls {
+                NullsOrder::First => std::cmp::Ordering::Less,
+                NullsOrder::Last => std::cmp::Ordering::Greater,
+            },
+            (Some(_), None) => match nulls {
+                NullsOrder::First => std::cmp::Ordering::Greater,
+                NullsOrder::Last => std::cmp::Ordering::Less,
+            },
+        });
+        results
+    }
+
+    /// Orders results by a float field in ascending order.
+    /// 
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field to order by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by_float(Product::price());
+    /// ```
+    pub fn order_by_float(&self, path: KeyPaths<T, f64>) -> Vec<T> {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            let a_val = path.get(a).cloned().unwrap_or(0.0);
+            let b_val = path.get(b).cloned().unwrap_or(0.0);
+            a_val.partial_cmp(&b_val).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results
+    }
+
+    /// Orders results by a float field in descending order.
+    /// 
+    /// **Note**: This method requires `T: Clone` as it creates owned sorted copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the f64 field to order by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by_float_desc(Product::rating());
+    /// ```
+    pub fn order_by_float_desc(&self, path: KeyPaths<T, f64>) -> Vec<T> {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            let a_val = path.get(a).cloned().unwrap_or(0.0);
+            let b_val = path.get(b).cloned().unwrap_or(0.0);
+            b_val.partial_cmp(&a_val).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results
+    }
+
+    /// Filters, sorts, counts, and pages results in one call.
+    ///
+    /// Matching items are collected once by reference (which yields the total
+    /// count), sorted by `sort`, and only the requested page is cloned. The sort
+    /// is stable, so items with equal keys keep their original order. Items
+    /// without a sort key (`None`) sort first in ascending order.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned copies of the page.
+    ///
+    /// # Arguments
+    ///
+    /// * `sort` - The key-path to the field to order by
+    /// * `desc` - Whether to sort in descending order
+    /// * `page` - The zero-based page number
+    /// * `per_page` - The number of items per page
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let page = query.list(Product::name(), false, 0, 20);
+    /// println!("Page 1 of {} ({} products)", page.total_pages, page.total);
+    /// ```
+    pub fn list<F>(&self, sort: KeyPaths<T, F>, desc: bool, page: usize, per_page: usize) -> Page<T>
+    where
+        F: Ord + Clone + 'static,
+    {
+        let mut matches: Vec<&T> = self.matching().collect();
+        let total = matches.len();
+
+        if desc {
+            matches.sort_by(|a, b| sort.get(b).cmp(&sort.get(a)));
+        } else {
+            matches.sort_by(|a, b| sort.get(a).cmp(&sort.get(b)));
+        }
+
+        let items = matches
+            .into_iter()
+            .skip(page.saturating_mul(per_page))
+            .take(per_page)
+            .cloned()
+            .collect();
+
+        Page {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: if per_page == 0 { 0 } else { total.div_ceil(per_page) },
+        }
+    }
+
+    /// Returns a page of sorted results as a JSON list-endpoint envelope.
+    ///
+    /// Runs `list(sort, desc, page, per_page)` and wraps the result as
+    /// `{ "items": [...], "page", "per_page", "total", "total_pages" }`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sort` - The key-path to the field to order by
+    /// * `desc` - Whether to sort in descending order
+    /// * `page` - The zero-based page number
+    /// * `per_page` - The number of items per page
+    ///
+    /// # Panics
+    ///
+    /// Panics if an item's `Serialize` implementation fails, e.g. a map with
+    /// non-string keys.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let body = query.page_json(Product::name(), false, 0, 20);
+    /// assert_eq!(body["total_pages"], 3);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn page_json<F>(&self, sort: KeyPaths<T, F>, desc: bool, page: usize, per_page: usize) -> serde_json::Value
+    where
+        F: Ord + Clone + 'static,
+        T: serde::Serialize,
+    {
+        let page = self.list(sort, desc, page, per_page);
+        serde_json::json!({
+            "items": page.items,
+            "page": page.page,
+            "per_page": page.per_page,
+            "total": page.total,
+            "total_pages": page.total_pages,
+        })
+    }
+
+    /// Groups results by a field value.
+    /// 
+    /// **Note**: This method requires `T: Clone` as it creates owned copies in groups.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to group by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_category = query.group_by(Product::category());
+    /// ```
+    pub fn group_by<F>(&self, path: KeyPaths<T, F>) -> HashMap<F, Vec<T>>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: HashMap<F, Vec<T>> = HashMap::new();
+
+        for item in self.matching() {
+            if let Some(key) = path.get(item).cloned() {
+                groups.entry(key).or_insert_with(Vec::new).push(item.clone());
+            }
+        }
+
+        groups
+    }
+
+    /// Groups results by a field value, keeping only groups with at least `min` items.
+    ///
+    /// Runs in two passes: the first counts group sizes without cloning, the
+    /// second clones only the items of qualifying groups, so small groups
+    /// never take memory.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned copies in groups.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to group by
+    /// * `min` - The minimum number of items a group needs to be kept
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Customers with at least 10 orders
+    /// let frequent = Query::new(&orders).group_by_min_size(Order::customer_id(), 10);
+    /// ```
+    pub fn group_by_min_size<F>(&self, path: KeyPaths<T, F>, min: usize) -> HashMap<F, Vec<T>>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut sizes: HashMap<&F, usize> = HashMap::new();
+        for item in self.matching() {
+            if let Some(key) = path.get(item) {
+                *sizes.entry(key).or_default() += 1;
+            }
+        }
+        sizes.retain(|_, size| *size >= min);
+
+        let mut groups: HashMap<F, Vec<T>> = HashMap::new();
+        for (index, item) in self.data.iter().enumerate() {
+            let Some(key) = path.get(item) else {
+                continue;
+            };
+            let Some(&size) = sizes.get(key) else {
+                continue;
+            };
+            if self.matches_at(index, item) {
+                groups
+                    .entry(key.clone())
+                    .or_insert_with(|| Vec::with_capacity(size))
+                    .push(item.clone());
+            }
+        }
+
+        groups
+    }
+
+    /// Groups results by a field value, keeping groups in first-seen order.
+    ///
+    /// Like `group_by`, but keys iterate in the order their first item appears
+    /// in the data, giving reproducible output for snapshots and UIs.
+    ///
+    /// **Note**: Requires the `indexmap` feature; `group_by_ordered_vec` gives
+    /// the same order without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to group by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let by_category = query.group_by_ordered(Product::category());
+    /// for (category, items) in &by_category {
+    ///     println!("{}: {}", category, items.len());
+    /// }
+    /// ```
+    #[cfg(feature = "indexmap")]
+    pub fn group_by_ordered<F>(&self, path: KeyPaths<T, F>) -> indexmap::IndexMap<F, Vec<T>>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: indexmap::IndexMap<F, Vec<T>> = indexmap::IndexMap::new();
+
+        for item in self.matching() {
+            if let Some(key) = path.get(item).cloned() {
+                groups.entry(key).or_default().push(item.clone());
+            }
+        }
+
+        groups
+    }
+
+    /// Groups results by a field value, returning groups in first-seen key order.
+    ///
+    /// Same ordering as `group_by_ordered`, without the `indexmap` feature:
+    /// groups are kept in a `Vec` with a key-to-position lookup on the side.
+    ///
+    /// **Note**: This method requires `T: Clone` as it creates owned copies in groups.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the field to group by
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (category, items) in query.group_by_ordered_vec(Product::category()) {
+    ///     println!("{}: {}", category, items.len());
+    /// }
+    /// ```
+    pub fn group_by_ordered_vec<F>(&self, path: KeyPaths<T, F>) -> Vec<(F, Vec<T>)>
+    where
+        F: Eq + std::hash::Hash + Clone + 'static,
+    {
+        let mut groups: Vec<(F, Vec<T>)> = Vec::new();
+        let mut positions: HashMap<F, usize> = HashMap::new();
+
+        for item in self.matching() {
+            let Some(key) = path.get(item) else {
+                continue;
+            };
+            let position = match positions.get(key) {
+                Some(&position) => position,
+                None => {
+                    positions.insert(key.clone(), groups.len());
+                    groups.push((key.clone(), Vec::new()));
+                    groups.len() - 1
+                }
+            };
+            groups[position].1.push(item.clone());
+        }
+
+        groups
+    }
+
+    /// Finds the item with the largest value in each group, in a single pass.
+    ///
+    /// Ties keep the first matching item. Items whose value is missing or
+    /// incomparable (e.g. `NaN`) are skipped, so a group holding only such
+    /// items is left out.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path to group by
+    /// * `value` - The key-path to the value being maximized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Top-selling product per category
+    /// let top = query.max_item_per_group(Product::category(), Product::sales());
+    /// ```
+    pub fn max_item_per_group<K, F>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, F>) -> HashMap<K, T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        self.extreme_item_per_group(key, value, |candidate, current| candidate > current)
+    }
+
+    /// Finds the item with the smallest value in each group, in a single pass.
+    ///
+    /// Ties keep the first matching item. Missing and incomparable values are
+    /// skipped as in [`max_item_per_group`](Self::max_item_per_group).
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path to group by
+    /// * `value` - The key-path to the value being minimized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cheapest = query.min_item_per_group(Product::category(), Product::price());
+    /// ```
+    pub fn min_item_per_group<K, F>(&self, key: KeyPaths<T, K>, value: KeyPaths<T, F>) -> HashMap<K, T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        self.extreme_item_per_group(key, value, |candidate, current| candidate < current)
+    }
+
+    fn extreme_item_per_group<K, F>(
+        &self,
+        key: KeyPaths<T, K>,
+        value: KeyPaths<T, F>,
+        replaces: impl Fn(&F, &F) -> bool,
+    ) -> HashMap<K, T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        let mut best: HashMap<K, &T> = HashMap::new();
+
+        for item in self.matching() {
+            let (Some(group), Some(candidate)) = (key.get(item).cloned(), value.get(item)) else {
+                continue;
+            };
+            // A value that doesn't compare with itself (NaN) could never be replaced.
+            if candidate.partial_cmp(candidate).is_none() {
+                continue;
+            }
+            match best.get_mut(&group) {
+                Some(current) => {
+                    if value.get(current).is_some_and(|current| replaces(candidate, current)) {
+                        *current = item;
+                    }
+                }
+                None => {
+                    best.insert(group, item);
+                }
+            }
+        }
+
+        best.into_iter().map(|(group, item)| (group, item.clone())).collect()
+    }
+
+    /// Deduplicates by key, keeping the item with the largest `by` value for each key.
+    ///
+    /// Keys appear in the order they are first seen. Ties keep the first
+    /// matching item, and items whose key or `by` value is missing are skipped.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path identifying duplicates
+    /// * `by` - The key-path to the value being maximized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Latest version of each document
+    /// let latest = query.unique_by_max(Document::slug(), Document::revision());
+    /// ```
+    pub fn unique_by_max<K, F>(&self, key: KeyPaths<T, K>, by: KeyPaths<T, F>) -> Vec<T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        self.unique_by_extreme(key, by, |candidate, current| candidate > current)
+    }
+
+    /// Deduplicates by key, keeping the item with the smallest `by` value for each key.
+    ///
+    /// Keys appear in the order they are first seen. Ties keep the first
+    /// matching item, and items whose key or `by` value is missing are skipped.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key-path identifying duplicates
+    /// * `by` - The key-path to the value being minimized
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Cheapest offer for each SKU
+    /// let best_offers = query.unique_by_min(Offer::sku(), Offer::price());
+    /// ```
+    pub fn unique_by_min<K, F>(&self, key: KeyPaths<T, K>, by: KeyPaths<T, F>) -> Vec<T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        self.unique_by_extreme(key, by, |candidate, current| candidate < current)
+    }
+
+    fn unique_by_extreme<K, F>(
+        &self,
+        key: KeyPaths<T, K>,
+        by: KeyPaths<T, F>,
+        replaces: impl Fn(&F, &F) -> bool,
+    ) -> Vec<T>
+    where
+        K: Eq + std::hash::Hash + Clone + 'static,
+        F: PartialOrd + 'static,
+    {
+        let mut positions: HashMap<K, usize> = HashMap::new();
+        let mut kept: Vec<&T> = Vec::new();
+
+        for item in self.matching() {
+            let (Some(group), Some(candidate)) = (key.get(item).cloned(), by.get(item)) else {
+                continue;
+            };
+            match positions.get(&group) {
+                Some(&position) => {
+                    if by.get(kept[position]).is_some_and(|current| replaces(candidate, current)) {
+                        kept[position] = item;
+                    }
+                }
+                None => {
+                    positions.insert(group, kept.len());
+                    kept.push(item);
+                }
+            }
+        }
+
+        kept.into_iter().cloned().collect()
+    }
+
+    /// Computes a trailing moving average of `value` over items sorted by `order`.
+    ///
+    /// Each item is paired with the average of its own value and up to
+    /// `window - 1` preceding values. The first items have fewer predecessors
+    /// and are averaged over what is available, so the output has one entry
+    /// per item. The sort is stable; items missing either field are skipped.
+    /// `NaN` values are left out of every average they fall in, so a window
+    /// holding only `NaN` averages to `NaN`.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The key-path to the field defining the series order
+    /// * `value` - The key-path to the value being averaged
+    /// * `window` - The number of points in each average
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // 7-day moving average of daily revenue
+    /// let smoothed = Query::new(&days).moving_avg(Day::date(), Day::revenue(), 7);
+    /// ```
+    pub fn moving_avg<F>(&self, order: KeyPaths<T, F>, value: KeyPaths<T, f64>, window: usize) -> Vec<(T, f64)>
+    where
+        F: PartialOrd + 'static,
+    {
+        assert!(window > 0, "window must be greater than zero");
+
+        let mut points: Vec<(&F, f64, &T)> = self.matching()
+            .filter_map(|item| Some((order.get(item)?, *value.get(item)?, item)))
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Each window is summed on its own so one NaN cannot carry into later windows.
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, _, item))| {
+                let start = (i + 1).saturating_sub(window);
+                let (sum, count) = points[start..=i]
+                    .iter()
+                    .filter(|(_, v, _)| !v.is_nan())
+                    .fold((0.0, 0), |(sum, count), (_, v, _)| (sum + v, count + 1));
+                (item.clone(), sum / count as f64)
+            })
+            .collect()
+    }
+
+    /// Pairs each item, sorted by `order`, with the largest `value` seen so far.
+    ///
+    /// This is the cumulative maximum (high-water mark) of the series. The
+    /// sort is stable, so items with equal order keys are processed in source
+    /// order; items missing either field are skipped. `NaN` values never
+    /// raise the maximum.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The key-path to the field defining the series order
+    /// * `value` - The key-path to the value being tracked
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Drawdown from the running peak
+    /// let drawdowns: Vec<f64> = Query::new(&closes)
+    ///     .running_max(Close::date(), Close::equity())
+    ///     .iter()
+    ///     .map(|(close, peak)| (peak - close.equity) / peak)
+    ///     .collect();
+    /// ```
+    pub fn running_max<F>(&self, order: KeyPaths<T, F>, value: KeyPaths<T, f64>) -> Vec<(T, f64)>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.running_extreme(order, value, f64::max)
+    }
+
+    /// Pairs each item, sorted by `order`, with the smallest `value` seen so far.
+    ///
+    /// The cumulative counterpart of [`running_max`](Self::running_max), with
+    /// the same ordering and skipping rules.
+    ///
+    /// **Note**: This method requires `T: Clone` as it returns owned items.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The key-path to the field defining the series order
+    /// * `value` - The key-path to the value being tracked
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let low_water = Query::new(&readings).running_min(Reading::time(), Reading::level());
+    /// ```
+    pub fn running_min<F>(&self, order: KeyPaths<T, F>, value: KeyPaths<T, f64>) -> Vec<(T, f64)>
+    where
+        F: PartialOrd + 'static,
+    {
+        self.running_extreme(order, value, f64::min)
+    }
+
+    fn running_extreme<F>(&self, order: KeyPaths<T, F>, value: KeyPaths<T, f64>, combine: fn(f64, f64) -> f64) -> Vec<(T, f64)>
+    where
+        F: PartialOrd + 'static,
+    {
+        let mut points: Vec<(&F, f64, &T)> = self.matching()
+            .filter_map(|item| Some((order.get(item)?, *value.get(item)?, item)))
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut extreme: Option<f64> = None;
+        points
+            .into_iter()
+            .map(|(_, v, item)| {
+                let current = extreme.map_or(v, |e| combine(e, v));
+                extreme = Some(current);
+                (item.clone(), current)
+            })
+            .collect()
+    }
+
+    // ============================================================================
+    // i64 DateTime Aggregators (Unix timestamps in milliseconds)
+    // ============================================================================
+
+    /// Finds the minimum i64 timestamp value.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let earliest = query.min_timestamp(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn min_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .min()
+    }
+
+    /// Finds the maximum i64 timestamp value.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let latest = query.max_timestamp(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn max_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .max()
+    }
+
+    /// Calculates the average of i64 timestamp values.
+    ///
+    /// Returns `None` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let avg_timestamp = query.avg_timestamp(Event::created_at()).unwrap_or(0);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn avg_timestamp(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        let items: Vec<i64> = self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .collect();
+
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.iter().sum::<i64>() / items.len() as i64)
+        }
+    }
+
+    /// Calculates the sum of i64 timestamp values.
+    ///
+    /// Returns `0` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let total_timestamp = query.sum_timestamp(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn sum_timestamp(&self, path: KeyPaths<T, i64>) -> i64 {
+        self.matching()
+            .filter_map(|item| path.get(item).cloned())
+            .sum()
+    }
+
+    /// Counts the number of non-null i64 timestamp values.
+    ///
+    /// Returns `0` if no items match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let timestamp_count = query.count_timestamp(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn count_timestamp(&self, path: KeyPaths<T, i64>) -> usize {
+        self.matching()
+            .filter(|item| path.get(item).is_some())
+            .count()
+    }
+
+    /// Filters by i64 timestamp being after a reference timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `reference` - The reference timestamp to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_after_timestamp(Event::created_at(), cutoff_timestamp);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_after_timestamp(self, path: KeyPaths<T, i64>, reference: i64) -> Self {
+        self.where_(path, move |timestamp| timestamp > &reference)
+    }
+
+    /// Filters by i64 timestamp being before a reference timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `reference` - The reference timestamp to compare against
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let old = query.where_before_timestamp(Event::created_at(), cutoff_timestamp);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_before_timestamp(self, path: KeyPaths<T, i64>, reference: i64) -> Self {
+        self.where_(path, move |timestamp| timestamp < &reference)
+    }
+
+    /// Filters by i64 timestamp being between two timestamps (inclusive).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `start` - The start timestamp
+    /// * `end` - The end timestamp
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let range = query.where_between_timestamp(Event::created_at(), start_ts, end_ts);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_between_timestamp(self, path: KeyPaths<T, i64>, start: i64, end: i64) -> Self {
+        self.where_(path, move |timestamp| timestamp >= &start && timestamp <= &end)
+    }
+
+    /// Filters by i64 timestamp being within the last N days from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `days` - Number of days to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_last_days_timestamp(Event::created_at(), 30);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_last_days_timestamp(self, path: KeyPaths<T, i64>, days: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now - (days * 24 * 60 * 60 * 1000); // Convert days to milliseconds
+        self.where_after_timestamp(path, cutoff)
+    }
+
+    /// Filters by i64 timestamp being within the next N days from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `days` - Number of days to look ahead
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let upcoming = query.where_next_days_timestamp(Event::scheduled_at(), 7);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_next_days_timestamp(self, path: KeyPaths<T, i64>, days: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now + (days * 24 * 60 * 60 * 1000); // Convert days to milliseconds
+        self.where_before_timestamp(path, cutoff)
+    }
+
+    /// Filters by i64 timestamp being within the last N hours from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `hours` - Number of hours to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_last_hours_timestamp(Event::created_at(), 24);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_last_hours_timestamp(self, path: KeyPaths<T, i64>, hours: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now - (hours * 60 * 60 * 1000); // Convert hours to milliseconds
+        self.where_after_timestamp(path, cutoff)
+    }
+
+    /// Filters by i64 timestamp being within the next N hours from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `hours` - Number of hours to look ahead
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let upcoming = query.where_next_hours_timestamp(Event::scheduled_at(), 2);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_next_hours_timestamp(self, path: KeyPaths<T, i64>, hours: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now + (hours * 60 * 60 * 1000); // Convert hours to milliseconds
+        self.where_before_timestamp(path, cutoff)
+    }
+
+    /// Filters by i64 timestamp being within the last N minutes from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `minutes` - Number of minutes to look back
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recent = query.where_last_minutes_timestamp(Event::created_at(), 60);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_last_minutes_timestamp(self, path: KeyPaths<T, i64>, minutes: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now - (minutes * 60 * 1000); // Convert minutes to milliseconds
+        self.where_after_timestamp(path, cutoff)
+    }
+
+    /// Filters by i64 timestamp being within the next N minutes from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    /// * `minutes` - Number of minutes to look ahead
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let upcoming = query.where_next_minutes_timestamp(Event::scheduled_at(), 30);
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn where_next_minutes_timestamp(self, path: KeyPaths<T, i64>, minutes: i64) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = now + (minutes * 60 * 1000); // Convert minutes to milliseconds
+        self.where_before_timestamp(path, cutoff)
+    }
+
+    /// Orders results by i64 timestamp in ascending order (oldest first).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by_timestamp(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn order_by_timestamp(&self, path: KeyPaths<T, i64>) -> Vec<T> {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            let a_val = path.get(a).cloned().unwrap_or(0);
+            let b_val = path.get(b).cloned().unwrap_or(0);
+            a_val.cmp(&b_val)
+        });
+        results
+    }
+
+    /// Orders results by i64 timestamp in descending order (newest first).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The key-path to the i64 timestamp field
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sorted = query.order_by_timestamp_desc(Event::created_at());
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn order_by_timestamp_desc(&self, path: KeyPaths<T, i64>) -> Vec<T> {
+        let mut results: Vec<T> = self.matching()
+            .cloned()
+            .collect();
+
+        results.sort_by(|a, b| {
+            let a_val = path.get(a).cloned().unwrap_or(0);
+            let b_val = path.get(b).cloned().unwrap_or(0);
+            b_val.cmp(&a_val)
+        });
+        results
+    }
+}
+
+/// Placement of items without a value when ordering with `order_by_nulls`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    /// Items without a value come before all others.
+    First,
+    /// Items without a value come after all others.
+    Last,
+}
+
+/// Percentile of non-empty, ascending `values`; `p` must be within `0..=100`.
+fn percentile_of_sorted(values: &[f64], p: f64, method: PercentileMethod) -> f64 {
+    let n = values.len();
+    match method {
+        PercentileMethod::NearestRank => {
+            let rank = ((p / 100.0) * n as f64).ceil() as usize;
+            values[rank.clamp(1, n) - 1]
+        }
+        PercentileMethod::Linear => {
+            let h = (n - 1) as f64 * p / 100.0;
+            let lower = h.floor() as usize;
+            let upper = (lower + 1).min(n - 1);
+            values[lower] + (h - lower as f64) * (values[upper] - values[lower])
+        }
+    }
+}
+
+/// Summarizes `values` in a single pass (Welford's algorithm), skipping NaN.
+///
+/// Returns `None` if no values remain.
+fn describe_values(values: impl IntoIterator<Item = f64>) -> Option<Describe> {
+    let mut stats: Option<Describe> = None;
+    let mut m2 = 0.0;
+
+    for value in values.into_iter().filter(|value| !value.is_nan()) {
+        let Some(stats) = stats.as_mut() else {
+            stats = Some(Describe { count: 1, sum: value, min: value, max: value, mean: value, stddev: 0.0 });
+            continue;
+        };
+        stats.count += 1;
+        stats.sum += value;
+        stats.min = stats.min.min(value);
+        stats.max = stats.max.max(value);
+        let delta = value - stats.mean;
+        stats.mean += delta / stats.count as f64;
+        m2 += delta * (value - stats.mean);
+    }
+
+    stats.map(|mut stats| {
+        if stats.count > 1 {
+            stats.stddev = (m2 / (stats.count - 1) as f64).sqrt();
+        }
+        stats
+    })
+}
+
+/// How `percentile_with` turns ranked values into a percentile.
+///
+/// For sorted values `x[0] <= ... <= x[n-1]` and a percentile `p` in `0..=100`:
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentileMethod {
+    /// Returns an actual data value: `x[max(ceil(p / 100 * n), 1) - 1]`.
+    NearestRank,
+    /// Interpolates between neighbours: with `h = (n - 1) * p / 100`,
+    /// returns `x[floor(h)] + (h - floor(h)) * (x[floor(h) + 1] - x[floor(h)])`.
+    ///
+    /// Matches numpy's default and Excel's `PERCENTILE.INC`.
+    Linear,
+}
+
+/// A filter predicate that can be shared across threads.
+///
+/// `Query` itself stays on one thread because key-path filters are not `Send`.
+/// A `SyncFilter` is `Send + Sync` and cheap to clone, so dynamic filter logic
+/// can be assembled once and handed to worker threads, each of which applies
+/// it with `Query::where_sync`, or combined into a `SyncQuery` that can itself
+/// be moved between threads. Build it from closures over the item rather
+/// than key-paths.
+///
+/// # Example
+///
+/// ```ignore
+/// let filter = SyncFilter::new(|p: &Product| p.price < 100.0)
+///     .and(SyncFilter::new(|p: &Product| p.stock > 0));
+///
+/// let products = Arc::new(products);
+/// let handle = std::thread::spawn({
+///     let products = products.clone();
+///     move || Query::new(&products).where_sync(&filter).count()
+/// });
+/// ```
+pub struct SyncFilter<T> {
+    predicate: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T> SyncFilter<T> {
+    /// Creates a filter from a thread-safe predicate.
+    pub fn new(predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Returns whether an item passes the filter.
+    pub fn matches(&self, item: &T) -> bool {
+        (self.predicate)(item)
+    }
+
+    /// Combines two filters so both must match.
+    pub fn and(self, other: SyncFilter<T>) -> Self
+    where
+        T: 'static,
+    {
+        Self::new(move |item| self.matches(item) && other.matches(item))
+    }
+
+    /// Combines two filters so either may match.
+    pub fn or(self, other: SyncFilter<T>) -> Self
+    where
+        T: 'static,
+    {
+        Self::new(move |item| self.matches(item) || other.matches(item))
+    }
+}
+
+impl<T> Clone for SyncFilter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            predicate: Arc::clone(&self.predicate),
+        }
+    }
+}
+
+/// Timing and scan counts for one query execution.
+///
+/// Created by calling `all_timed()` on a `Query`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryStats {
+    /// The number of items the filters were evaluated against.
+    pub items_scanned: usize,
+    /// The number of items that passed every filter.
+    pub items_passed: usize,
+    /// The wall-clock time the execution took.
+    pub elapsed: Duration,
+}
+
+/// Summary statistics of a float field.
+///
+/// Created by calling `describe()` on a `Query`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Describe {
+    /// The number of values summarized.
+    pub count: usize,
+    /// The sum of the values.
+    pub sum: f64,
+    /// The smallest value.
+    pub min: f64,
+    /// The largest value.
+    pub max: f64,
+    /// The arithmetic mean.
+    pub mean: f64,
+    /// The sample standard deviation; `0.0` for a single value.
+    pub stddev: f64,
+}
+
+/// A page of sorted results together with pagination metadata.
+///
+/// Created by calling `list()` on a `Query`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// The items on this page.
+    pub items: Vec<T>,
+    /// The total number of matching items across all pages.
+    pub total: usize,
+    /// The zero-based page number.
+    pub page: usize,
+    /// The requested page size.
+    pub per_page: usize,
+    /// The total number of pages (`0` when there are no matches).
+    pub total_pages: usize,
+}
+
+/// Helper struct for pagination after a skip operation.
+///
+/// Created by calling `skip()` on a `Query`.
+pub struct QueryWithSkip<'a, 'b, T: 'static> {
+    query: &'b Query<'a, T>,
+    offset: usize,
+}
+
+impl<'a, 'b, T: 'static> QueryWithSkip<'a, 'b, T> {
+    /// Returns up to `n` items after skipping the offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The maximum number of items to return
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let page_2 = query.skip(20).limit(10);
+    /// ```
+    pub fn limit(&self, n: usize) -> Vec<&'a T> {
+        self.query
+            .matching()
+            .skip(self.offset)
+            .take(n)
+            .collect()
+    }
+}
+
+/// Matching items paired with a cached computed value.
+///
+/// Created by calling `with_computed()` on a `Query`. Operations here read the
+/// cached values, so the derivation is never run again.
+pub struct ComputedQuery<'a, T: 'static, U> {
+    rows: Vec<(&'a T, U)>,
+}
+
+impl<'a, T: 'static, U> ComputedQuery<'a, T, U> {
+    /// Keeps only the rows whose computed value satisfies `predicate`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let high_margin = query.with_computed(margin).where_computed(|&m| m > 0.3);
+    /// ```
+    pub fn where_computed(mut self, predicate: impl Fn(&U) -> bool) -> Self {
+        self.rows.retain(|(_, value)| predicate(value));
+        self
+    }
+
+    /// Returns the number of rows.
+    pub fn count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the items, in match order.
+    pub fn all(&self) -> Vec<&'a T> {
+        self.rows.iter().map(|(item, _)| *item).collect()
+    }
+
+    /// Returns each item with its computed value, in match order.
+    pub fn rows(&self) -> &[(&'a T, U)] {
+        &self.rows
+    }
+
+    /// Returns the computed values, in match order.
+    pub fn values(&self) -> Vec<U>
+    where
+        U: Clone,
+    {
+        self.rows.iter().map(|(_, value)| value.clone()).collect()
+    }
+
+    /// Sums the computed values; `U::default()` if there are no rows.
+    pub fn sum_computed(&self) -> U
+    where
+        U: Clone + std::ops::Add<Output = U> + Default,
+    {
+        self.rows.iter().fold(U::default(), |acc, (_, value)| acc + value.clone())
+    }
+}
+
+impl<'a, T: 'static> ComputedQuery<'a, T, f64> {
+    /// Averages the computed values, or `None` if there are no rows.
+    pub fn avg_computed(&self) -> Option<f64> {
+        if self.rows.is_empty() {
+            None
+        } else {
+            Some(self.sum_computed() / self.rows.len() as f64)
+        }
+    }
+}
+
+    // Parallel operations (only available with parallel feature)
+    #[cfg(feature = "parallel")]
+    impl<'a, T: 'static + Send + Sync> Query<'a, T> {
+    /// Get all items using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results = query.all_parallel();
+    /// ```
+    pub fn all_parallel(&self) -> Vec<&'a T> {
+        use rayon::prelude::*;
+        self.data.par_iter().collect()
+    }
+
+    /// Count all items using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let count = query.count_parallel();
+    /// ```
+    pub fn count_parallel(&self) -> usize {
+        use rayon::prelude::*;
+        self.data.par_iter().count()
+    }
+
+    /// Check if any items exist using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let exists = query.exists_parallel();
+    /// ```
+    pub fn exists_parallel(&self) -> bool {
+        use rayon::prelude::*;
+        self.data.par_iter().any(|_| true)
+    }
+
+    /// Find minimum value using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let min = query.min_parallel(Product::price());
+    /// ```
+    pub fn min_parallel<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    where
+        F: Ord + Clone + 'static + Send + Sync,
+    {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .min()
+    }
+
+    /// Find maximum value using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let max = query.max_parallel(Product::price());
+    /// ```
+    pub fn max_parallel<F>(&self, path: KeyPaths<T, F>) -> Option<F>
+    where
+        F: Ord + Clone + 'static + Send + Sync,
+    {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .max()
+    }
+
+    /// Compute sum using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sum = query.sum_parallel(Product::price());
+    /// ```
+    pub fn sum_parallel<F>(&self, path: KeyPaths<T, F>) -> F
+    where
+        F: Clone + std::ops::Add<Output = F> + Default + 'static + Send + Sync + std::iter::Sum,
+    {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .sum()
+    }
+
+    /// Compute average using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let avg = query.avg_parallel(Product::price());
+    /// ```
+    pub fn avg_parallel(&self, path: KeyPaths<T, f64>) -> Option<f64> {
+        use rayon::prelude::*;
+        let items: Vec<f64> = self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .collect();
+
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.par_iter().sum::<f64>() / items.len() as f64)
+        }
+    }
+
+    /// Find minimum i64 timestamp using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let earliest = query.min_timestamp_parallel(Event::created_at());
+    /// ```
+    pub fn min_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .min()
+    }
+
+    /// Find maximum i64 timestamp using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let latest = query.max_timestamp_parallel(Event::created_at());
+    /// ```
+    pub fn max_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .max()
+    }
+
+    /// Compute average i64 timestamp using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let avg = query.avg_timestamp_parallel(Event::created_at());
+    /// ```
+    pub fn avg_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> Option<i64> {
+        use rayon::prelude::*;
+        let items: Vec<i64> = self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .collect();
+
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.par_iter().sum::<i64>() / items.len() as i64)
+        }
+    }
+
+    /// Compute sum of i64 timestamps using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let total = query.sum_timestamp_parallel(Event::created_at());
+    /// ```
+    pub fn sum_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> i64 {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter_map(|item| path.get(item).cloned())
+            .sum()
+    }
+
+    /// Count i64 timestamps using parallel processing.
+    /// Note: This method ignores filters for thread safety.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let count = query.count_timestamp_parallel(Event::created_at());
+    /// ```
+    pub fn count_timestamp_parallel(&self, path: KeyPaths<T, i64>) -> usize {
+        use rayon::prelude::*;
+        self.data
+            .par_iter()
+            .filter(|item| path.get(item).is_some())
+            .count()
+    }
+}
+
+/// Flattens grouped results into a list of groups sorted by key, with each
+/// group's items sorted by a field.
+///
+/// This is the usual shape for a grouped report rendered as one list. Sorting
+/// is stable at both levels; items whose `within` key-path yields no value
+/// come first in their group.
+///
+/// # Arguments
+///
+/// * `groups` - Grouped results, e.g. from `Query::group_by`
+/// * `within` - The key-path to order items within each group
+///
+/// # Example
+///
+/// ```ignore
+/// let report = flatten_groups_sorted(
+///     Query::new(&products).group_by(Product::category()),
+///     Product::price(),
+/// );
+/// for (category, items) in report {
+///     println!("{}: {} items", category, items.len());
+/// }
+/// ```
+pub fn flatten_groups_sorted<K, T, F>(groups: HashMap<K, Vec<T>>, within: KeyPaths<T, F>) -> Vec<(K, Vec<T>)>
+where
+    K: Ord,
+    F: Ord + 'static,
+{
+    let mut flattened: Vec<(K, Vec<T>)> = groups.into_iter().collect();
+    flattened.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (_, items) in flattened.iter_mut() {
+        items.sort_by(|a, b| within.get(a).cmp(&within.get(b)));
+    }
+    flattened
+}
+
+/// Compares two result sets, returning `(only_in_a, only_in_b)`.
+///
+/// Intended for regression tests: run the old and new version of a query and
+/// assert both sides are empty. Items are compared by value with `Eq` and
+/// `Hash`; order does not matter. Each side keeps its own order, and an item
+/// repeated in one input but present in the other is not reported.
+///
+/// # Arguments
+///
+/// * `a` - The first result set
+/// * `b` - The second result set
+///
+/// # Example
+///
+/// ```ignore
+/// let old = Query::new(&orders).where_(Order::total(), |&t| t > 100);
+/// let new = Query::new(&orders).where_item(|o| o.total >= 101);
+/// let (only_old, only_new) = result_diff(&old.all(), &new.all());
+/// assert!(only_old.is_empty() && only_new.is_empty());
+/// ```
+pub fn result_diff<'a, T>(a: &[&'a T], b: &[&'a T]) -> (Vec<&'a T>, Vec<&'a T>)
+where
+    T: Eq + std::hash::Hash,
+{
+    let in_a: HashSet<&T> = a.iter().copied().collect();
+    let in_b: HashSet<&T> = b.iter().copied().collect();
+    (
+        a.iter().copied().filter(|item| !in_b.contains(item)).collect(),
+        b.iter().copied().filter(|item| !in_a.contains(item)).collect(),
+    )
+}
+
+/// Turns a key-path into a JSON value extractor for `Query::select_records`.
+///
+/// A missing value becomes `null`.
+///
+/// # Panics
+///
+/// The returned extractor panics if the value's `Serialize` implementation
+/// fails, e.g. a map with non-string keys.
+///
+/// # Example
+///
+/// ```ignore
+/// let rows = query.select_records(&[("price", record_field(Product::price()))]);
+/// ```
+#[cfg(feature = "serde")]
+pub fn record_field<T, F>(path: KeyPaths<T, F>) -> Box<dyn Fn(&T) -> serde_json::Value>
+where
+    T: 'static,
+    F: serde::Serialize + 'static,
+{
+    Box::new(move |item| serde_json::to_value(path.get(item)).expect("field must serialize to JSON"))
+}
+
+/// Returns whether the Levenshtein distance between `a` and `b` is at most `max`.
+fn within_distance(a: &str, b: &[char], max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        // Distances never decrease between rows, so the limit is already exceeded.
+        if row_min > max {
+            return false;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()] <= max
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use key_paths_derive::Keypath;
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    pub(crate) struct Event {
+        pub(crate) id: u32,
+        pub(crate) kind: String,
+        pub(crate) timestamp: i64,
+    }
+
+    pub(crate) fn event(id: u32, kind: &str, timestamp: i64) -> Event {
+        Event {
+            id,
+            kind: kind.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_count_per_window() {
+        let events = vec![
+            event(1, "click", 999),
+            event(2, "click", 1_000),
+            event(3, "view", 1_999),
+            event(4, "click", 2_000),
+            event(5, "click", 0),
+            event(6, "click", 5_500),
+        ];
+
+        let windows = Query::new(&events).count_per_window(Event::timestamp(), 1_000);
+        let expected: Vec<(i64, usize)> = vec![(0, 2), (1_000, 2), (2_000, 1), (5_000, 1)];
+        assert_eq!(windows.into_iter().collect::<Vec<_>>(), expected);
+
+        let clicks = Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .count_per_window(Event::timestamp(), 1_000);
+        assert_eq!(clicks.get(&1_000), Some(&1));
+    }
+
+    #[test]
+    fn test_count_per_window_negative_timestamps() {
+        let events = vec![event(1, "click", -1), event(2, "click", -1_000), event(3, "click", -1_001)];
+
+        let windows = Query::new(&events).count_per_window(Event::timestamp(), 1_000);
+        assert_eq!(windows.into_iter().collect::<Vec<_>>(), vec![(-2_000, 1), (-1_000, 2)]);
+    }
+
+    #[test]
+    fn test_count_per_window_extreme_timestamps() {
+        let events = vec![
+            event(1, "click", i64::MIN),
+            event(2, "click", i64::MIN + 1),
+            event(3, "click", i64::MAX),
+            event(4, "click", -1),
+        ];
+
+        let windows = Query::new(&events).count_per_window(Event::timestamp(), 1_000);
+        let expected = vec![(i64::MIN, 2), (-1_000, 1), (i64::MAX - 807, 1)];
+        assert_eq!(windows.into_iter().collect::<Vec<_>>(), expected);
+
+        let halves = Query::new(&events).count_per_window(Event::timestamp(), i64::MAX);
+        assert_eq!(halves.into_iter().collect::<Vec<_>>(), vec![(i64::MIN, 1), (-i64::MAX, 2), (i64::MAX, 1)]);
+    }
+
+    #[test]
+    fn test_total_ignores_filters() {
+        let events = vec![event(1, "click", 0), event(2, "view", 0), event(3, "click", 0)];
+
+        let query = Query::new(&events).where_(Event::kind(), |k| k == "view");
+        assert_eq!(query.count(), 1);
+        assert_eq!(query.total(), 3);
+        assert!(!query.is_empty_source());
+
+        let none = Query::new(&events).where_(Event::id(), |&id| id > 10);
+        assert_eq!(none.count(), 0);
+        assert_eq!(none.total(), 3);
+        assert!(!none.is_empty_source());
+
+        let empty: Vec<Event> = Vec::new();
+        let query = Query::new(&empty);
+        assert_eq!(query.total(), 0);
+        assert!(query.is_empty_source());
+    }
+
+    #[test]
+    fn test_comparison_filters() {
+        let events = vec![event(1, "click", 100), event(2, "view", 200), event(3, "click", 300)];
+        let ids = |query: Query<Event>| query.all().iter().map(|e| e.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(Query::new(&events).where_gt(Event::timestamp(), 200)), vec![3]);
+        assert_eq!(ids(Query::new(&events).where_ge(Event::timestamp(), 200)), vec![2, 3]);
+        assert_eq!(ids(Query::new(&events).where_lt(Event::timestamp(), 200)), vec![1]);
+        assert_eq!(ids(Query::new(&events).where_le(Event::timestamp(), 200)), vec![1, 2]);
+        assert_eq!(ids(Query::new(&events).where_eq(Event::id(), 2)), vec![2]);
+        assert_eq!(ids(Query::new(&events).where_ne(Event::kind(), "click".to_string())), vec![2]);
+    }
+
+    #[test]
+    fn test_comparison_filters_float_equality_is_exact() {
+        let values = vec![0.1 + 0.2, 0.3];
+        let path = KeyPaths::readable(|v: &f64| v);
+
+        assert_eq!(Query::new(&values).where_eq(path.clone(), 0.3).count(), 1);
+        assert_eq!(Query::new(&values).where_ne(path, 0.3).count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "indexmap")]
+    fn test_group_by_ordered_keeps_first_seen_order() {
+        let events = vec![
+            event(1, "view", 0),
+            event(2, "click", 0),
+            event(3, "view", 0),
+            event(4, "scroll", 0),
+            event(5, "click", 0),
+            event(6, "abandon", 0),
+        ];
+
+        let groups = Query::new(&events).group_by_ordered(Event::kind());
+        let keys: Vec<&str> = groups.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["view", "click", "scroll", "abandon"]);
+
+        let view_ids: Vec<u32> = groups["view"].iter().map(|e| e.id).collect();
+        assert_eq!(view_ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_group_by_ordered_vec_keeps_first_seen_order() {
+        let events = vec![
+            event(1, "scroll", 0),
+            event(2, "view", 0),
+            event(3, "scroll", 0),
+            event(4, "abandon", 0),
+            event(5, "view", 0),
+            event(6, "click", 0),
+            event(7, "abandon", 0),
+        ];
+
+        let groups = Query::new(&events).group_by_ordered_vec(Event::kind());
+        let summary: Vec<(&str, Vec<u32>)> = groups
+            .iter()
+            .map(|(kind, items)| (kind.as_str(), items.iter().map(|e| e.id).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("scroll", vec![1, 3]),
+                ("view", vec![2, 5]),
+                ("abandon", vec![4, 7]),
+                ("click", vec![6]),
+            ]
+        );
+
+        let filtered = Query::new(&events).where_(Event::id(), |&id| id > 3);
+        let keys: Vec<String> = filtered.group_by_ordered_vec(Event::kind()).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["abandon", "view", "click"]);
+    }
+
+    #[test]
+    fn test_select_map2() {
+        #[derive(Debug, PartialEq)]
+        struct EventSummary {
+            id: u32,
+            kind: String,
+        }
+
+        let events = vec![event(1, "click", 0), event(2, "view", 0), event(3, "click", 0)];
+
+        let summaries = Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .select_map2(Event::id(), Event::kind(), |id, kind| EventSummary { id, kind });
+        assert_eq!(
+            summaries,
+            vec![
+                EventSummary { id: 1, kind: "click".to_string() },
+                EventSummary { id: 3, kind: "click".to_string() },
+            ]
+        );
+
+        let tags = vec![vec![1, 2], vec![], vec![3]];
+        let first = KeyPaths::failable_readable(|v: &Vec<u32>| v.first());
+        let last = KeyPaths::failable_readable(|v: &Vec<u32>| v.last());
+        let pairs = Query::new(&tags).select_map2(first, last, |a, b| (a, b));
+        assert_eq!(pairs, vec![(1, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_empty_result_aggregations() {
+        let events = vec![event(1, "click", 100), event(2, "view", 200)];
+        let query = Query::new(&events).where_(Event::kind(), |k| k == "scroll");
+        let timestamps = KeyPaths::readable(|e: &Event| &e.timestamp);
+
+        assert!(query.is_empty_result());
+        assert!(!query.exists());
+        assert_eq!(query.count(), 0);
+        assert!(query.all().is_empty());
+        assert!(query.first().is_none());
+        assert!(query.select(Event::id()).is_empty());
+        assert_eq!(query.sum(Event::id()), 0);
+        assert_eq!(query.sum_opt(Event::id()), None);
+        assert_eq!(query.min(Event::id()), None);
+        assert_eq!(query.max(Event::id()), None);
+        assert!(query.count_per_window(timestamps, 1_000).is_empty());
+
+        let floats: Vec<f64> = Vec::new();
+        let value = KeyPaths::readable(|v: &f64| v);
+        let query = Query::new(&floats);
+        assert!(query.is_empty_result());
+        assert_eq!(query.avg(value.clone()), None);
+        assert_eq!(query.min_float(value.clone()), None);
+        assert_eq!(query.max_float(value.clone()), None);
+        assert_eq!(query.sum(value.clone()), 0.0);
+        assert_eq!(query.sum_opt(value), None);
+    }
+
+    #[test]
+    #[cfg(feature = "datetime")]
+    fn test_empty_result_timestamp_aggregations() {
+        let events = vec![event(1, "click", 100)];
+        let query = Query::new(&events).where_(Event::id(), |&id| id > 1);
+
+        assert_eq!(query.min_timestamp(Event::timestamp()), None);
+        assert_eq!(query.max_timestamp(Event::timestamp()), None);
+        assert_eq!(query.avg_timestamp(Event::timestamp()), None);
+        assert_eq!(query.sum_timestamp(Event::timestamp()), 0);
+        assert_eq!(query.count_timestamp(Event::timestamp()), 0);
+    }
+
+    #[test]
+    fn test_sum_opt() {
+        let events = vec![event(1, "click", 100), event(2, "view", 200), event(3, "click", 300)];
+
+        let clicks = Query::new(&events).where_(Event::kind(), |k| k == "click");
+        assert_eq!(clicks.sum_opt(Event::timestamp()), Some(400));
+        assert_eq!(clicks.sum(Event::timestamp()), 400);
+        assert!(!clicks.is_empty_result());
+    }
+
+    #[test]
+    fn test_where_item() {
+        let events = vec![event(1, "click", 5), event(2, "view", 1), event(3, "click", 2)];
+
+        let query = Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .where_item(|e| e.timestamp > e.id as i64);
+        let ids: Vec<u32> = query.all().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_list_pages_sorted_results() {
+        let events: Vec<Event> = (1..=7).map(|id| event(id, "click", (id as i64 * 37) % 10)).collect();
+        let query = Query::new(&events).where_(Event::id(), |&id| id != 4);
+
+        let page = query.list(Event::timestamp(), false, 0, 4);
+        assert_eq!(page.total, 6);
+        assert_eq!(page.total_pages, 2);
+        assert_eq!((page.page, page.per_page), (0, 4));
+        let timestamps: Vec<i64> = page.items.iter().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2, 4, 5]);
+
+        let last = query.list(Event::timestamp(), false, 1, 4);
+        let timestamps: Vec<i64> = last.items.iter().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![7, 9]);
+        assert_eq!(last.total, 6);
+
+        let desc = query.list(Event::timestamp(), true, 0, 3);
+        let timestamps: Vec<i64> = desc.items.iter().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![9, 7, 5]);
+
+        let past_end = query.list(Event::timestamp(), false, 5, 4);
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total_pages, 2);
+
+        let exact = query.list(Event::id(), false, 0, 3);
+        assert_eq!(exact.total_pages, 2);
+        assert_eq!(exact.items.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_and_also_or_else() {
+        let events: Vec<Event> = (1..=6)
+            .map(|id| event(id, if id % 2 == 0 { "click" } else { "view" }, id as i64 * 100))
+            .collect();
+        let clicks = Query::new(&events).where_(Event::kind(), |k| k == "click");
+        let late = Query::new(&events).where_(Event::timestamp(), |&t| t >= 400);
+
+        let ids = |items: Vec<&Event>| items.iter().map(|e| e.id).collect::<Vec<_>>();
+        let expected_and: Vec<u32> = events
+            .iter()
+            .filter(|e| e.kind == "click" && e.timestamp >= 400)
+            .map(|e| e.id)
+            .collect();
+        let expected_or: Vec<u32> = events
+            .iter()
+            .filter(|e| e.kind == "click" || e.timestamp >= 400)
+            .map(|e| e.id)
+            .collect();
+
+        assert_eq!(ids(clicks.and_also(&late)), expected_and);
+        assert_eq!(ids(clicks.or_else(&late)), expected_or);
+        assert_eq!(ids(late.and_also(&clicks)), vec![4, 6]);
+        assert_eq!(ids(clicks.or_else(&late)), vec![2, 4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same slice")]
+    fn test_and_also_rejects_different_sources() {
+        let a = vec![event(1, "click", 0)];
+        let b = a.clone();
+        Query::new(&a).and_also(&Query::new(&b));
+    }
+
+    #[derive(Debug, Clone, Keypath)]
+    struct Listing {
+        id: u32,
+        discount: Option<u32>,
+    }
+
+    #[test]
+    fn test_order_by_nulls_all_combinations() {
+        let listings = vec![
+            Listing { id: 1, discount: Some(20) },
+            Listing { id: 2, discount: None },
+            Listing { id: 3, discount: Some(5) },
+            Listing { id: 4, discount: None },
+            Listing { id: 5, discount: Some(10) },
+        ];
+        let query = Query::new(&listings);
+        let ids = |items: Vec<Listing>| items.iter().map(|l| l.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(query.order_by_nulls(Listing::discount(), NullsOrder::First)), vec![2, 4, 3, 5, 1]);
+        assert_eq!(ids(query.order_by_nulls(Listing::discount(), NullsOrder::Last)), vec![3, 5, 1, 2, 4]);
+        assert_eq!(ids(query.order_by_nulls_desc(Listing::discount(), NullsOrder::First)), vec![2, 4, 1, 5, 3]);
+        assert_eq!(ids(query.order_by_nulls_desc(Listing::discount(), NullsOrder::Last)), vec![1, 5, 3, 2, 4]);
+    }
+
+    #[test]
+    fn test_where_similar() {
+        let events = vec![
+            event(1, "checkout", 0),
+            event(2, "chekout", 0),
+            event(3, "chckoutt", 0),
+            event(4, "login", 0),
+            event(5, "", 0),
+        ];
+        let ids = |query: Query<Event>| query.all().iter().map(|e| e.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(Query::new(&events).where_similar(Event::kind(), "checkout", 0)), vec![1]);
+        assert_eq!(ids(Query::new(&events).where_similar(Event::kind(), "checkout", 1)), vec![1, 2]);
+        assert_eq!(ids(Query::new(&events).where_similar(Event::kind(), "checkout", 2)), vec![1, 2, 3]);
+        assert_eq!(ids(Query::new(&events).where_similar(Event::kind(), "logout", 1)), Vec::<u32>::new());
+        assert_eq!(ids(Query::new(&events).where_similar(Event::kind(), "log", 5)), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_within_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert!(within_distance("kitten", &chars("sitting"), 3));
+        assert!(!within_distance("kitten", &chars("sitting"), 2));
+        assert!(within_distance("", &chars(""), 0));
+        assert!(within_distance("café", &chars("cafe"), 1));
+        assert!(!within_distance("abcdef", &chars("uvwxyz"), 5));
+    }
+
+    #[test]
+    fn test_max_and_min_item_per_group() {
+        let events = vec![
+            event(1, "click", 30),
+            event(2, "view", 10),
+            event(3, "click", 50),
+            event(4, "click", 50),
+            event(5, "view", 5),
+            event(6, "view", 5),
+            event(7, "login", 20),
+        ];
+        let query = Query::new(&events);
+
+        let latest = query.max_item_per_group(Event::kind(), Event::timestamp());
+        assert_eq!(latest.len(), 3);
+        assert_eq!(latest["click"].id, 3);
+        assert_eq!(latest["view"].id, 2);
+        assert_eq!(latest["login"].id, 7);
+
+        let earliest = query.min_item_per_group(Event::kind(), Event::timestamp());
+        assert_eq!(earliest["click"].id, 1);
+        assert_eq!(earliest["view"].id, 5);
+        assert_eq!(earliest["login"].id, 7);
+
+        let filtered = Query::new(&events)
+            .where_(Event::id(), |&id| id != 3)
+            .max_item_per_group(Event::kind(), Event::timestamp());
+        assert_eq!(filtered["click"].id, 4);
+    }
+
+    #[test]
+    fn test_extreme_item_per_group_skips_leading_nan() {
+        let readings = vec![
+            Reading { sensor: 1, value: f64::NAN },
+            Reading { sensor: 1, value: 2.0 },
+            Reading { sensor: 1, value: 7.0 },
+            Reading { sensor: 1, value: f64::NAN },
+            Reading { sensor: 2, value: f64::NAN },
+        ];
+        let query = Query::new(&readings);
+
+        let max = query.max_item_per_group(Reading::sensor(), Reading::value());
+        assert_eq!(max[&1].value, 7.0);
+        assert!(!max.contains_key(&2));
+
+        let min = query.min_item_per_group(Reading::sensor(), Reading::value());
+        assert_eq!(min[&1].value, 2.0);
+        assert_eq!(min.len(), 1);
+    }
+
+    #[derive(Clone, Keypath)]
+    struct Customer {
+        id: u32,
+    }
+
+    #[derive(Clone, Keypath)]
+    struct Purchase {
+        customer_id: u32,
+        status: String,
+    }
+
+    #[test]
+    fn test_where_in_subquery_matches_manual_filter() {
+        let customers: Vec<Customer> = (1..=6).map(|id| Customer { id }).collect();
+        let purchases = vec![
+            Purchase { customer_id: 2, status: "completed".to_string() },
+            Purchase { customer_id: 3, status: "pending".to_string() },
+            Purchase { customer_id: 5, status: "completed".to_string() },
+            Purchase { customer_id: 2, status: "completed".to_string() },
+            Purchase { customer_id: 9, status: "completed".to_string() },
+        ];
+
+        let completed = Query::new(&purchases)
+            .where_(Purchase::status(), |s| s == "completed")
+            .select(Purchase::customer_id());
+
+        let manual: Vec<u32> = Query::new(&customers)
+            .where_(Customer::id(), {
+                let completed = completed.clone();
+                move |id| completed.contains(id)
+            })
+            .all()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        let subquery: Vec<u32> = Query::new(&customers)
+            .where_in_subquery(Customer::id(), completed)
+            .all()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(subquery, manual);
+        assert_eq!(subquery, vec![2, 5]);
+        assert_eq!(Query::new(&customers).where_in_subquery(Customer::id(), Vec::new()).count(), 0);
+    }
+
+    #[test]
+    #[allow(clippy::type_complexity)]
+    fn test_count_by_predicate_overlapping_buckets() {
+        let events = vec![
+            event(1, "click", 5),
+            event(2, "click", 50),
+            event(3, "view", 500),
+            event(4, "login", 5000),
+        ];
+        let buckets: Vec<(&str, Box<dyn Fn(&Event) -> bool>)> = vec![
+            ("clicks", Box::new(|e: &Event| e.kind == "click")),
+            ("recent", Box::new(|e: &Event| e.timestamp >= 50)),
+            ("ancient", Box::new(|e: &Event| e.timestamp < 0)),
+        ];
+
+        let counts = Query::new(&events).count_by_predicate(&buckets);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["clicks"], 2);
+        assert_eq!(counts["recent"], 3);
+        assert_eq!(counts["ancient"], 0);
+
+        let filtered = Query::new(&events)
+            .where_(Event::id(), |&id| id != 2)
+            .count_by_predicate(&buckets);
+        assert_eq!(filtered["clicks"], 1);
+        assert_eq!(filtered["recent"], 2);
+    }
+
+    #[test]
+    fn test_order_by_then_id_breaks_ties_by_id() {
+        let events = vec![
+            event(9, "view", 1),
+            event(4, "click", 2),
+            event(7, "view", 3),
+            event(1, "view", 4),
+            event(6, "click", 5),
+        ];
+        let ids = |items: Vec<Event>| items.iter().map(|e| e.id).collect::<Vec<_>>();
+
+        let asc = Query::new(&events).order_by_then_id(Event::kind(), Event::id());
+        assert_eq!(ids(asc), vec![4, 6, 1, 7, 9]);
+
+        let desc = Query::new(&events).order_by_desc_then_id(Event::kind(), Event::id());
+        assert_eq!(ids(desc), vec![1, 7, 9, 4, 6]);
+
+        let mut reversed = events.clone();
+        reversed.reverse();
+        assert_eq!(
+            ids(Query::new(&reversed).order_by_then_id(Event::kind(), Event::id())),
+            vec![4, 6, 1, 7, 9]
+        );
+    }
+
+    #[test]
+    fn test_group_by_ranges_edges_and_overflow() {
+        let prices = [-5.0, 0.0, 9.99, 10.0, 49.0, 50.0, 100.0, 250.0, f64::NAN];
+        let items: Vec<(u32, f64)> = prices.iter().enumerate().map(|(i, &p)| (i as u32, p)).collect();
+        let price = KeyPaths::readable(|item: &(u32, f64)| &item.1);
+        let query = Query::new(&items);
+
+        let bins = query.group_by_ranges(price.clone(), &[0.0, 10.0, 50.0, 100.0]);
+        let ids = |bin: usize| bins.get(&bin).map(|v| v.iter().map(|i| i.0).collect::<Vec<_>>());
+        assert_eq!(ids(0), Some(vec![0]));
+        assert_eq!(ids(1), Some(vec![1, 2]));
+        assert_eq!(ids(2), Some(vec![3, 4]));
+        assert_eq!(ids(3), Some(vec![5]));
+        assert_eq!(ids(4), Some(vec![6, 7]));
+        assert_eq!(bins.values().map(Vec::len).sum::<usize>(), 8);
+
+        let no_edges = query.group_by_ranges(price.clone(), &[]);
+        assert_eq!(no_edges.keys().copied().collect::<Vec<_>>(), vec![0]);
+
+        let sparse = query.group_by_ranges(price, &[1000.0]);
+        assert_eq!(sparse.keys().copied().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bounds must be sorted in ascending order")]
+    fn test_group_by_ranges_rejects_unsorted_bounds() {
+        let items = vec![1.0];
+        Query::new(&items).group_by_ranges(KeyPaths::readable(|v: &f64| v), &[10.0, 5.0]);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let events = vec![
+            event(1, "click", 0),
+            event(2, "view", 0),
+            event(1, "login", 0),
+            event(3, "click", 0),
+            event(1, "view", 0),
+            event(3, "view", 0),
+        ];
+
+        let duplicates = Query::new(&events).find_duplicates(Event::id());
+        assert_eq!(duplicates.len(), 2);
+        assert!(!duplicates.contains_key(&2));
+        let kinds = |id: u32| duplicates[&id].iter().map(|e| e.kind.as_str()).collect::<Vec<_>>();
+        assert_eq!(kinds(1), vec!["click", "login", "view"]);
+        assert_eq!(kinds(3), vec!["click", "view"]);
+
+        let views_only = Query::new(&events)
+            .where_(Event::kind(), |k| k == "view")
+            .find_duplicates(Event::id());
+        assert!(views_only.is_empty());
+    }
+
+    #[test]
+    fn test_percentile_methods_match_reference_values() {
+        let values = vec![15.0, 50.0, 35.0, 20.0, 40.0, f64::NAN];
+        let query = Query::new(&values);
+        let path = || KeyPaths::readable(|v: &f64| v);
+
+        // Reference: nearest-rank by hand, linear from numpy.percentile([15, 20, 35, 40, 50], p)
+        assert_eq!(query.percentile_with(path(), 40.0, PercentileMethod::NearestRank), Some(20.0));
+        assert_eq!(query.percentile_with(path(), 40.0, PercentileMethod::Linear), Some(29.0));
+        assert_eq!(query.percentile_with(path(), 75.0, PercentileMethod::NearestRank), Some(40.0));
+        assert_eq!(query.percentile_with(path(), 75.0, PercentileMethod::Linear), Some(40.0));
+        assert_eq!(query.percentile_with(path(), 0.0, PercentileMethod::NearestRank), Some(15.0));
+        assert_eq!(query.percentile_with(path(), 100.0, PercentileMethod::Linear), Some(50.0));
+        assert_eq!(query.percentile(path(), 50.0), Some(35.0));
+
+        assert_eq!(query.percentile_with(path(), 101.0, PercentileMethod::Linear), None);
+        assert_eq!(query.percentile_with(path(), -1.0, PercentileMethod::NearestRank), None);
+        let empty: Vec<f64> = Vec::new();
+        assert_eq!(Query::new(&empty).percentile(path(), 50.0), None);
+    }
+
+    #[test]
+    fn test_take_n_per_group_caps_in_source_order() {
+        let events = vec![
+            event(1, "click", 0),
+            event(2, "view", 0),
+            event(3, "click", 0),
+            event(4, "click", 0),
+            event(5, "login", 0),
+            event(6, "view", 0),
+            event(7, "click", 0),
+        ];
+        let query = Query::new(&events);
+        let ids = |items: &Vec<&Event>| items.iter().map(|e| e.id).collect::<Vec<_>>();
+
+        let capped = query.take_n_per_group(Event::kind(), 2);
+        assert_eq!(capped.len(), 3);
+        assert_eq!(ids(&capped["click"]), vec![1, 3]);
+        assert_eq!(ids(&capped["view"]), vec![2, 6]);
+        assert_eq!(ids(&capped["login"]), vec![5]);
+
+        let filtered = Query::new(&events)
+            .where_(Event::id(), |&id| id > 1)
+            .take_n_per_group(Event::kind(), 2);
+        assert_eq!(ids(&filtered["click"]), vec![3, 4]);
+
+        assert!(query.take_n_per_group(Event::kind(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_where_sync_filter_runs_on_spawned_thread() {
+        let events = Arc::new(vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "login", 40),
+        ]);
+        let filter = SyncFilter::new(|e: &Event| e.kind == "click")
+            .or(SyncFilter::new(|e: &Event| e.kind == "login"))
+            .and(SyncFilter::new(|e: &Event| e.timestamp > 10));
+
+        let handle = std::thread::spawn({
+            let events = Arc::clone(&events);
+            let filter = filter.clone();
+            move || {
+                Query::new(&events)
+                    .where_sync(&filter)
+                    .all()
+                    .iter()
+                    .map(|e| e.id)
+                    .collect::<Vec<_>>()
+            }
+        });
+
+        assert_eq!(handle.join().unwrap(), vec![3, 4]);
+        assert_eq!(Query::new(&events).where_sync(&filter).count(), 2);
+    }
+
+    #[test]
+    fn test_count_any_first_where_match_chained_filters() {
+        let events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "click", 40),
+        ];
+        let clicks = Query::new(&events).where_(Event::kind(), |k| k == "click");
+
+        let chained = || {
+            Query::new(&events)
+                .where_(Event::kind(), |k| k == "click")
+                .where_(Event::timestamp(), |&t| t > 15)
+        };
+        assert_eq!(clicks.count_where(Event::timestamp(), |&t| t > 15), chained().count());
+        assert_eq!(clicks.any_where(Event::timestamp(), |&t| t > 15), chained().exists());
+        assert_eq!(
+            clicks.first_where(Event::timestamp(), |&t| t > 15).map(|e| e.id),
+            chained().first().map(|e| e.id)
+        );
+        assert_eq!(clicks.first_where(Event::timestamp(), |&t| t > 15).map(|e| e.id), Some(3));
+
+        assert!(!clicks.any_where(Event::timestamp(), |&t| t == 20));
+        assert!(clicks.first_where(Event::timestamp(), |&t| t > 100).is_none());
+        // The builder is not modified by the terminal predicates.
+        assert_eq!(clicks.count(), 3);
+    }
+
+    #[test]
+    fn test_all_match_stops_at_first_violation() {
+        use std::cell::Cell;
+
+        let events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "click", 40),
+            event(5, "click", 50),
+        ];
+        let clicks = Query::new(&events).where_(Event::kind(), |k| k == "click");
+
+        let calls = Cell::new(0);
+        let below_35 = |&t: &i64| {
+            calls.set(calls.get() + 1);
+            t < 35
+        };
+        assert!(!clicks.all_match(Event::timestamp(), below_35));
+        // Clicks at 10 and 30 pass, 40 fails; the click at 50 is never checked.
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let positive = |&t: &i64| {
+            calls.set(calls.get() + 1);
+            t > 0
+        };
+        assert!(clicks.all_match(Event::timestamp(), positive));
+        assert_eq!(calls.get(), 4);
+
+        let none = Query::new(&events).where_(Event::kind(), |k| k == "scroll");
+        assert!(none.all_match(Event::timestamp(), |_| false));
+    }
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Region {
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Buyer {
+        region: Region,
+        tier: Option<u32>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Keypath)]
+    struct Sale {
+        id: u32,
+        buyer: Buyer,
+        total: f64,
+    }
+
+    fn sales() -> Vec<Sale> {
+        let sale = |id, region: &str, tier, total| Sale {
+            id,
+            buyer: Buyer {
+                region: Region { name: region.to_string() },
+                tier,
+            },
+            total,
+        };
+        vec![
+            sale(1, "north", Some(2), 10.0),
+            sale(2, "south", None, 25.0),
+            sale(3, "north", Some(1), 5.0),
+            sale(4, "east", Some(3), 40.0),
+        ]
+    }
+
+    #[test]
+    fn test_moving_avg_trailing_window_of_three() {
+        let mut sales = sales();
+        sales.reverse();
+        sales.push(Sale { id: 5, ..sales[3].clone() });
+
+        // Totals ordered by id: 10, 25, 5, 40, 10
+        let averages: Vec<(u32, f64)> = Query::new(&sales)
+            .moving_avg(Sale::id(), Sale::total(), 3)
+            .into_iter()
+            .map(|(sale, avg)| (sale.id, avg))
+            .collect();
+        let expected = [
+            (1, 10.0),
+            (2, (10.0 + 25.0) / 2.0),
+            (3, (10.0 + 25.0 + 5.0) / 3.0),
+            (4, (25.0 + 5.0 + 40.0) / 3.0),
+            (5, (5.0 + 40.0 + 10.0) / 3.0),
+        ];
+        assert_eq!(averages.len(), expected.len());
+        for ((id, avg), (expected_id, expected_avg)) in averages.iter().zip(expected) {
+            assert_eq!(*id, expected_id);
+            assert!((avg - expected_avg).abs() < 1e-9);
+        }
+
+        let single = Query::new(&sales).moving_avg(Sale::id(), Sale::total(), 1);
+        assert!(single.iter().all(|(sale, avg)| sale.total == *avg));
+    }
+
+    #[test]
+    fn test_moving_avg_recovers_after_nan() {
+        let readings: Vec<Reading> = [1.0, f64::NAN, 3.0, 5.0, 7.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| Reading { sensor: i as u32, value })
+            .collect();
+
+        let averages: Vec<f64> = Query::new(&readings)
+            .moving_avg(Reading::sensor(), Reading::value(), 2)
+            .into_iter()
+            .map(|(_, avg)| avg)
+            .collect();
+        assert_eq!(averages, vec![1.0, 1.0, 3.0, 4.0, 6.0]);
+
+        let single = Query::new(&readings).moving_avg(Reading::sensor(), Reading::value(), 1);
+        assert!(single[1].1.is_nan());
+        assert_eq!(single[4].1, 7.0);
+    }
+
+    #[test]
+    fn test_describe_matches_independent_calculations() {
+        let sales = sales();
+        let totals: Vec<f64> = sales.iter().map(|s| s.total).collect();
+        let n = totals.len() as f64;
+        let mean = totals.iter().sum::<f64>() / n;
+        let variance = totals.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        let stats = Query::new(&sales).describe(Sale::total()).unwrap();
+        assert_eq!(stats.count, 4);
+        assert!((stats.sum - 80.0).abs() < 1e-9);
+        assert_eq!(stats.min, 5.0);
+        assert_eq!(stats.max, 40.0);
+        assert!((stats.mean - mean).abs() < 1e-9);
+        assert!((stats.stddev - variance.sqrt()).abs() < 1e-9);
+
+        let single = Query::new(&sales).where_(Sale::id(), |&id| id == 2).describe(Sale::total()).unwrap();
+        assert_eq!((single.count, single.mean, single.stddev), (1, 25.0, 0.0));
+        assert!(Query::new(&sales).where_(Sale::id(), |&id| id > 10).describe(Sale::total()).is_none());
+    }
+
+    #[test]
+    fn test_count_distinct_by_group_matches_manual_count() {
+        let mut sales = sales();
+        sales.push(Sale { id: 5, ..sales[0].clone() });
+        sales.push(Sale { id: 6, ..sales[3].clone() });
+        let region = || Sale::buyer().then(Buyer::region()).then(Region::name());
+        let tier = || Sale::buyer().then(Buyer::tier());
+
+        let counts = Query::new(&sales).count_distinct_by_group(region(), tier());
+
+        let mut manual: HashMap<String, HashSet<u32>> = HashMap::new();
+        for sale in &sales {
+            if let Some(tier) = sale.buyer.tier {
+                manual.entry(sale.buyer.region.name.clone()).or_default().insert(tier);
+            }
+        }
+        let manual: HashMap<String, usize> = manual.into_iter().map(|(k, v)| (k, v.len())).collect();
+        assert_eq!(counts, manual);
+        assert_eq!(counts["north"], 2);
+        assert_eq!(counts["east"], 1);
+        // The only south sale has no tier, so the group is absent.
+        assert!(!counts.contains_key("south"));
+    }
+
+    #[test]
+    fn test_group_share_fractions_sum_to_one() {
+        let sales = sales();
+        let region = || Sale::buyer().then(Buyer::region()).then(Region::name());
+
+        let shares = Query::new(&sales).group_share(region(), Sale::total());
+        assert_eq!(shares["north"], (15.0, 0.1875));
+        assert_eq!(shares["south"], (25.0, 0.3125));
+        assert_eq!(shares["east"], (40.0, 0.5));
+        let fractions: f64 = shares.values().map(|(_, share)| share).sum();
+        assert!((fractions - 1.0).abs() < 1e-9);
+
+        let zero: Vec<Sale> = sales.into_iter().map(|s| Sale { total: 0.0, ..s }).collect();
+        let shares = Query::new(&zero).group_share(region(), Sale::total());
+        assert_eq!(shares.len(), 3);
+        assert!(shares.values().all(|&(sum, share)| sum == 0.0 && share == 0.0));
+    }
+
+    #[test]
+    fn test_fold_report_matches_separate_aggregations() {
+        #[derive(Default)]
+        struct Report {
+            count: usize,
+            revenue: f64,
+            regions: HashSet<String>,
+            max_total: Option<f64>,
+        }
+
+        let sales = sales();
+        let query = Query::new(&sales).where_(Sale::total(), |&t| t > 5.0);
+        let report = query.fold_report(Report::default(), |r, sale| {
+            r.count += 1;
+            r.revenue += sale.total;
+            r.regions.insert(sale.buyer.region.name.clone());
+            r.max_total = Some(r.max_total.map_or(sale.total, |m| m.max(sale.total)));
+        });
+
+        assert_eq!(report.count, query.count());
+        assert_eq!(report.revenue, query.sum(Sale::total()));
+        assert_eq!(report.max_total, query.max_float(Sale::total()));
+        let regions: HashSet<String> = query
+            .all()
+            .into_iter()
+            .map(|s| s.buyer.region.name.clone())
+            .collect();
+        assert_eq!(report.regions, regions);
+        assert_eq!(report.regions.len(), 3);
+
+        let empty = Query::new(&sales).where_(Sale::id(), |&id| id > 10);
+        assert_eq!(empty.fold_report(0usize, |n, _| *n += 1), 0);
+    }
+
+    #[test]
+    fn test_nested_key_paths_group_order_and_aggregate() {
+        let sales = sales();
+        let region = || Sale::buyer().then(Buyer::region()).then(Region::name());
+        let tier = || Sale::buyer().then(Buyer::tier());
+        let query = Query::new(&sales);
+
+        let groups = query.group_by(region());
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["north"].iter().map(|s| s.id).collect::<Vec<_>>(), vec![1, 3]);
+
+        let ordered = query.order_by(region());
+        assert_eq!(ordered.iter().map(|s| s.id).collect::<Vec<_>>(), vec![4, 1, 3, 2]);
+
+        let north_total: f64 = Query::new(&sales)
+            .where_(region(), |r| r == "north")
+            .sum(Sale::total());
+        assert_eq!(north_total, 15.0);
+
+        // A failable segment (Option field) skips items where it is None.
+        assert_eq!(query.max(tier()), Some(3));
+        assert_eq!(query.select(tier()), vec![2, 1, 3]);
+        let by_tier = query.order_by_nulls(tier(), NullsOrder::Last);
+        assert_eq!(by_tier.iter().map(|s| s.id).collect::<Vec<_>>(), vec![3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_flatten_groups_sorted_orders_both_levels() {
+        let events = vec![
+            event(1, "view", 30),
+            event(2, "click", 20),
+            event(3, "view", 10),
+            event(4, "login", 5),
+            event(5, "click", 20),
+            event(6, "click", 1),
+        ];
+
+        let report = flatten_groups_sorted(Query::new(&events).group_by(Event::kind()), Event::timestamp());
+        let shape: Vec<(String, Vec<u32>)> = report
+            .into_iter()
+            .map(|(kind, items)| (kind, items.iter().map(|e| e.id).collect()))
+            .collect();
+
+        assert_eq!(
+            shape,
+            vec![
+                ("click".to_string(), vec![6, 2, 5]),
+                ("login".to_string(), vec![4]),
+                ("view".to_string(), vec![3, 1]),
+            ]
+        );
+        assert!(flatten_groups_sorted(HashMap::<String, Vec<Event>>::new(), Event::id()).is_empty());
+    }
+
+    #[test]
+    fn test_assert_all_passes_when_every_item_matches() {
+        let events = vec![event(1, "click", 10), event(2, "view", 20), event(3, "click", 30)];
+        Query::new(&events).assert_all(Event::timestamp(), |&t| t > 0, "timestamps must be positive");
+        Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .assert_all(Event::id(), |&id| id % 2 == 1, "clicks have odd ids");
+    }
+
+    #[test]
+    #[should_panic(expected = "clicks must be recent: matching item #1 violates the predicate: Event { id: 3, kind: \"click\", timestamp: 5 }")]
+    fn test_assert_all_reports_first_offender() {
+        let events = vec![event(1, "click", 10), event(2, "view", 1), event(3, "click", 5), event(4, "click", 2)];
+        Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .assert_all(Event::timestamp(), |&t| t > 8, "clicks must be recent");
+    }
+
+    #[test]
+    fn test_all_timed_and_timed() {
+        let events: Vec<Event> = (0..10_000)
+            .map(|i| event(i, if i % 3 == 0 { "click" } else { "view" }, i as i64))
+            .collect();
+        let query = Query::new(&events).where_(Event::kind(), |k| k == "click");
+
+        let outer = std::time::Instant::now();
+        let (results, stats) = query.all_timed();
+        let outer = outer.elapsed();
+        assert_eq!(stats.items_scanned, 10_000);
+        assert_eq!(stats.items_passed, results.len());
+        assert_eq!(stats.items_passed, query.count());
+        assert!(stats.items_scanned >= stats.items_passed);
+        // Coarse clocks may report zero, so only bound the time from above.
+        assert!(stats.elapsed <= outer);
+
+        let outer = std::time::Instant::now();
+        let (count, elapsed) = query.timed(|q| q.count());
+        assert_eq!(count, 3334);
+        assert!(elapsed <= outer.elapsed());
+    }
+
+    #[test]
+    fn test_select_scaled() {
+        let values = vec![10.0, 20.0, 15.0, 30.0, f64::NAN];
+        let path = || KeyPaths::readable(|v: &f64| v);
+
+        let scaled = Query::new(&values).select_scaled(path());
+        assert_eq!(&scaled[..4], &[0.0, 0.5, 0.25, 1.0]);
+        assert!(scaled[4].is_nan());
+
+        let filtered = Query::new(&values)
+            .where_(path(), |&v| v < 25.0)
+            .select_scaled(path());
+        assert_eq!(filtered, vec![0.0, 1.0, 0.5]);
+
+        let equal = vec![7.0, 7.0, 7.0];
+        assert_eq!(Query::new(&equal).select_scaled(path()), vec![0.0, 0.0, 0.0]);
+
+        let empty: Vec<f64> = Vec::new();
+        assert!(Query::new(&empty).select_scaled(path()).is_empty());
+    }
+
+    #[test]
+    fn test_select_case_first_arm_wins_with_default() {
+        let events = vec![
+            event(1, "click", 5),
+            event(2, "view", 50),
+            event(3, "click", 500),
+            event(4, "login", 5000),
+        ];
+
+        let labels = Query::new(&events).select_case(
+            vec![
+                (Box::new(|e: &Event| e.timestamp < 100), "early"),
+                (Box::new(|e: &Event| e.kind == "click"), "click"),
+                (Box::new(|e: &Event| e.timestamp < 1000), "mid"),
+            ],
+            "other",
+        );
+        assert_eq!(labels, vec!["early", "early", "click", "other"]);
+
+        let filtered = Query::new(&events)
+            .where_(Event::kind(), |k| k != "view")
+            .select_case(vec![(Box::new(|e: &Event| e.id > 3), 1)], 0);
+        assert_eq!(filtered, vec![0, 0, 1]);
+        assert_eq!(Query::new(&events).select_case(Vec::new(), "none").len(), 4);
+    }
+
+    #[test]
+    fn test_unique_by_max_and_min_keep_best_duplicate() {
+        let events = vec![
+            event(1, "click", 300),
+            event(2, "view", 100),
+            event(3, "click", 900),
+            event(4, "login", 50),
+            event(5, "view", 700),
+            event(6, "click", 100),
+            event(7, "view", 700),
+        ];
+        let query = Query::new(&events);
+        let ids = |items: Vec<Event>| items.iter().map(|e| e.id).collect::<Vec<_>>();
+
+        // Keys keep first-seen order: click, view, login. Ties keep the first item.
+        assert_eq!(ids(query.unique_by_max(Event::kind(), Event::timestamp())), vec![3, 5, 4]);
+        assert_eq!(ids(query.unique_by_min(Event::kind(), Event::timestamp())), vec![6, 2, 4]);
+
+        let without_login = Query::new(&events)
+            .where_(Event::kind(), |k| k != "login")
+            .unique_by_max(Event::kind(), Event::timestamp());
+        assert_eq!(ids(without_login), vec![3, 5]);
+    }
+
+    #[test]
+    fn test_where_fields_cmp_and_eq() {
+        use std::cmp::Ordering;
+
+        let listings = vec![
+            Listing { id: 1, discount: Some(20) },
+            Listing { id: 2, discount: None },
+            Listing { id: 3, discount: Some(3) },
+            Listing { id: 4, discount: Some(2) },
+        ];
+        let ids = |query: Query<Listing>| query.all().iter().map(|l| l.id).collect::<Vec<_>>();
+
+        // discount < id; the listing without a discount is excluded either way.
+        assert_eq!(
+            ids(Query::new(&listings).where_fields_cmp(Listing::discount(), Listing::id(), Ordering::Less)),
+            vec![4]
+        );
+        assert_eq!(
+            ids(Query::new(&listings).where_fields_cmp(Listing::discount(), Listing::id(), Ordering::Greater)),
+            vec![1]
+        );
+        assert_eq!(ids(Query::new(&listings).where_fields_eq(Listing::discount(), Listing::id())), vec![3]);
+    }
+
+    #[derive(Keypath)]
+    struct RegionSale {
+        region: String,
+        product: String,
+        amount: f64,
+    }
+
+    #[test]
+    fn test_pivot_sums_region_by_product() {
+        let sale = |region: &str, product: &str, amount| RegionSale {
+            region: region.to_string(),
+            product: product.to_string(),
+            amount,
+        };
+        let rows = vec![
+            sale("north", "laptop", 1000.0),
+            sale("north", "mouse", 20.0),
+            sale("south", "laptop", 900.0),
+            sale("north", "laptop", 1100.0),
+            sale("south", "mouse", 25.0),
+            sale("east", "mouse", 15.0),
+        ];
+
+        let table = Query::new(&rows).pivot(RegionSale::region(), RegionSale::product(), RegionSale::amount());
+
+        let mut expected: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for row in &rows {
+            *expected
+                .entry(row.region.clone())
+                .or_default()
+                .entry(row.product.clone())
+                .or_default() += row.amount;
+        }
+        assert_eq!(table, expected);
+        assert_eq!(table["north"]["laptop"], 2100.0);
+        assert!(!table["east"].contains_key("laptop"));
+
+        let big_only = Query::new(&rows)
+            .where_(RegionSale::amount(), |&a| a >= 100.0)
+            .pivot(RegionSale::region(), RegionSale::product(), RegionSale::amount());
+        assert_eq!(big_only.len(), 2);
+        assert_eq!(big_only["south"].len(), 1);
+    }
+
+    #[test]
+    fn test_first_array_fills_available_matches() {
+        let events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "click", 40),
+            event(5, "click", 50),
+        ];
+        let ids = |slots: &[Option<&Event>]| slots.iter().map(|e| e.map(|e| e.id)).collect::<Vec<_>>();
+
+        let fewer = Query::new(&events).where_(Event::kind(), |k| k == "view").first_array::<3>();
+        assert_eq!(ids(&fewer), vec![Some(2), None, None]);
+
+        let more = Query::new(&events).where_(Event::kind(), |k| k == "click").first_array::<3>();
+        assert_eq!(ids(&more), vec![Some(1), Some(3), Some(4)]);
+
+        let none: [Option<&Event>; 0] = Query::new(&events).first_array();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_group_by_min_size_drops_small_groups() {
+        let events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "login", 40),
+            event(5, "click", 50),
+            event(6, "view", 60),
+        ];
+        let ids = |items: &[Event]| items.iter().map(|e| e.id).collect::<Vec<_>>();
+
+        let groups = Query::new(&events).group_by_min_size(Event::kind(), 2);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(ids(&groups["click"]), vec![1, 3, 5]);
+        assert_eq!(ids(&groups["view"]), vec![2, 6]);
+        assert!(!groups.contains_key("login"));
+
+        // Sizes are counted after filtering.
+        let late = Query::new(&events)
+            .where_(Event::timestamp(), |&t| t > 25)
+            .group_by_min_size(Event::kind(), 2);
+        assert_eq!(late.len(), 1);
+        assert_eq!(ids(&late["click"]), vec![3, 5]);
+
+        assert!(Query::new(&events).group_by_min_size(Event::kind(), 4).is_empty());
+        assert_eq!(Query::new(&events).group_by_min_size(Event::kind(), 0).len(), 3);
+    }
+
+    #[derive(Keypath)]
+    struct Ping {
+        id: u32,
+        at: SystemTime,
+    }
+
+    fn pings() -> Vec<Ping> {
+        let now = SystemTime::now();
+        let hours = |h: u64| Duration::from_secs(h * 60 * 60);
+        vec![
+            Ping { id: 1, at: now - Duration::from_secs(30) },
+            Ping { id: 2, at: now - hours(3) },
+            Ping { id: 3, at: now - hours(30) },
+            Ping { id: 4, at: now - hours(24 * 10) },
+            Ping { id: 5, at: now + hours(1) },
+        ]
+    }
+
+    #[test]
+    fn test_relative_systemtime_filters() {
+        let pings = pings();
+        let ids = |query: Query<Ping>| query.all().iter().map(|p| p.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(Query::new(&pings).where_last_hours_systemtime(Ping::at(), 1)), vec![1, 5]);
+        assert_eq!(ids(Query::new(&pings).where_last_hours_systemtime(Ping::at(), 24)), vec![1, 2, 5]);
+        assert_eq!(ids(Query::new(&pings).where_last_days_systemtime(Ping::at(), 2)), vec![1, 2, 3, 5]);
+        assert_eq!(
+            ids(Query::new(&pings).where_within_systemtime(Ping::at(), Duration::from_secs(60))),
+            vec![1, 5]
+        );
+        assert_eq!(Query::new(&pings).where_within_systemtime(Ping::at(), Duration::MAX).count(), 5);
+    }
+
+    #[test]
+    fn test_find_map_stops_at_first_some() {
+        let events = vec![
+            event(1, "view", 10),
+            event(2, "click", 20),
+            event(3, "click", 30),
+            event(4, "error", 40),
+            event(5, "error", 50),
+            event(6, "error", 60),
+        ];
+        let calls = std::cell::Cell::new(0);
+
+        let first_error = Query::new(&events)
+            .where_(Event::kind(), |k| k != "view")
+            .find_map(|e| {
+                calls.set(calls.get() + 1);
+                (e.kind == "error").then(|| format!("error at {}", e.timestamp))
+            });
+        assert_eq!(first_error.as_deref(), Some("error at 40"));
+        // Events 2, 3 and 4 are inspected; the view is filtered out and 5 and 6 are never reached.
+        assert_eq!(calls.get(), 3);
+
+        assert_eq!(Query::new(&events).find_map(|e| (e.timestamp > 100).then_some(e.id)), None);
+    }
+
+    #[derive(Keypath)]
+    struct Request {
+        endpoint: String,
+        latency_ms: f64,
+    }
+
+    #[test]
+    fn test_count_le_thresholds_matches_independent_counts() {
+        let requests: Vec<Request> = [40.0, 100.0, 150.0, 200.0, 250.0, 499.0, 800.0, f64::NAN]
+            .iter()
+            .map(|&latency_ms| Request { endpoint: "/api".to_string(), latency_ms })
+            .collect();
+        let query = Query::new(&requests);
+
+        let thresholds = [500.0, 100.0, 200.0, 100.0, -1.0, f64::NAN];
+        let counts = query.count_le_thresholds(Request::latency_ms(), &thresholds);
+        let expected: Vec<usize> = thresholds
+            .iter()
+            .map(|&t| Query::new(&requests).where_(Request::latency_ms(), move |&l| l <= t).count())
+            .collect();
+        assert_eq!(counts, expected);
+        assert_eq!(counts, vec![6, 2, 4, 2, 0, 0]);
+
+        assert!(query.count_le_thresholds(Request::latency_ms(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_percentile_by_group_p95() {
+        let mut requests = Vec::new();
+        // /fast: 1..=100 ms, /slow: 10, 20, ..., 200 ms, /empty: only NaN.
+        for i in 1..=100 {
+            requests.push(Request { endpoint: "/fast".to_string(), latency_ms: i as f64 });
+        }
+        for i in 1..=20 {
+            requests.push(Request { endpoint: "/slow".to_string(), latency_ms: i as f64 * 10.0 });
+        }
+        requests.push(Request { endpoint: "/empty".to_string(), latency_ms: f64::NAN });
+
+        let p95 = Query::new(&requests).percentile_by_group(Request::endpoint(), Request::latency_ms(), 95.0);
+        assert_eq!(p95.len(), 2);
+        // h = 99 * 0.95 = 94.05 -> 95 + 0.05 * (96 - 95)
+        assert!((p95["/fast"] - 95.05).abs() < 1e-9);
+        // h = 19 * 0.95 = 18.05 -> 190 + 0.05 * (200 - 190)
+        assert!((p95["/slow"] - 190.5).abs() < 1e-9);
+
+        let fast_only = Query::new(&requests).where_(Request::endpoint(), |e| e == "/fast");
+        assert_eq!(
+            fast_only.percentile_by_group(Request::endpoint(), Request::latency_ms(), 95.0)["/fast"],
+            fast_only.percentile(Request::latency_ms(), 95.0).unwrap()
+        );
+        assert!(Query::new(&requests)
+            .percentile_by_group(Request::endpoint(), Request::latency_ms(), 101.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_indices_match_item_positions() {
+        let mut events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "view", 40),
+            event(5, "click", 50),
+        ];
+        let query = Query::new(&events).where_(Event::kind(), |k| k == "click");
+        let indices = query.indices();
+        assert_eq!(indices, vec![0, 2, 4]);
+        let matched: Vec<&Event> = indices.iter().map(|&i| &events[i]).collect();
+        assert_eq!(matched, query.all());
+
+        for i in indices {
+            events[i].timestamp += 1;
+        }
+        assert_eq!(events.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![11, 20, 31, 40, 51]);
+        assert!(Query::new(&events).where_(Event::id(), |&id| id > 5).indices().is_empty());
+    }
+
+    #[test]
+    fn test_largest_group_winner_and_tie() {
+        let events = vec![
+            event(1, "view", 10),
+            event(2, "click", 20),
+            event(3, "click", 30),
+            event(4, "view", 40),
+            event(5, "click", 50),
+            event(6, "login", 60),
+        ];
+
+        assert_eq!(Query::new(&events).largest_group(Event::kind()), Some(("click".to_string(), 3)));
+
+        // view and click tie at 2; view was seen first.
+        let tied = Query::new(&events)
+            .where_(Event::id(), |&id| id != 5)
+            .largest_group(Event::kind());
+        assert_eq!(tied, Some(("view".to_string(), 2)));
+
+        assert_eq!(Query::new(&events).where_(Event::id(), |&id| id > 6).largest_group(Event::kind()), None);
+    }
+
+    #[test]
+    fn test_select_columns_are_aligned() {
+        #[derive(Keypath)]
+        struct Quote {
+            symbol: &'static str,
+            bid: f64,
+            ask: Option<f64>,
+        }
+
+        let quotes = vec![
+            Quote { symbol: "AAA", bid: 10.0, ask: Some(10.5) },
+            Quote { symbol: "BBB", bid: 20.0, ask: None },
+            Quote { symbol: "CCC", bid: 30.0, ask: Some(30.5) },
+            Quote { symbol: "DDD", bid: 40.0, ask: Some(40.5) },
+        ];
+        let query = Query::new(&quotes).where_(Quote::symbol(), |&s| s != "CCC");
+
+        let columns = query.select_columns(&[("bid", Quote::bid()), ("ask", Quote::ask())]);
+        assert_eq!(columns.len(), 2);
+        assert!(columns.values().all(|c| c.len() == query.count()));
+        assert_eq!(columns["bid"], vec![10.0, 20.0, 40.0]);
+        // BBB has no ask, so its row holds NaN rather than shifting later rows.
+        let ask = &columns["ask"];
+        assert_eq!((ask[0], ask[2]), (10.5, 40.5));
+        assert!(ask[1].is_nan());
+    }
+
+    #[test]
+    fn test_with_computed_derives_once_per_item() {
+        let sales = sales();
+        let calls = std::cell::Cell::new(0);
+        let query = Query::new(&sales).where_(Sale::id(), |&id| id != 3);
+
+        let taxed = query.with_computed(|sale| {
+            calls.set(calls.get() + 1);
+            sale.total * 1.5
+        });
+        assert_eq!(taxed.values(), vec![15.0, 37.5, 60.0]);
+        assert_eq!(taxed.sum_computed(), 112.5);
+
+        let large = taxed.where_computed(|&t| t > 20.0);
+        assert_eq!(large.count(), 2);
+        assert_eq!(large.all().iter().map(|s| s.id).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(large.avg_computed(), Some(48.75));
+        assert_eq!(large.rows()[0].1, 37.5);
+
+        // One call per matching item, however many operations ran.
+        assert_eq!(calls.get(), 3);
+        assert_eq!(large.where_computed(|_| false).avg_computed(), None);
+    }
+
+    #[test]
+    fn test_sum_fields_matches_individual_sums() {
+        #[derive(Keypath)]
+        struct Usage {
+            host: u32,
+            cpu: f64,
+            memory: f64,
+            disk: Option<f64>,
+        }
+
+        let usage = vec![
+            Usage { host: 1, cpu: 0.5, memory: 2.0, disk: Some(10.0) },
+            Usage { host: 2, cpu: 1.5, memory: 4.0, disk: None },
+            Usage { host: 3, cpu: 0.25, memory: 8.0, disk: Some(30.0) },
+        ];
+        let query = Query::new(&usage).where_(Usage::host(), |&h| h != 1);
+
+        let sums = query.sum_fields(&[Usage::cpu(), Usage::memory(), Usage::disk()]);
+        assert_eq!(
+            sums,
+            vec![query.sum(Usage::cpu()), query.sum(Usage::memory()), query.sum(Usage::disk())]
+        );
+        assert_eq!(sums, vec![1.75, 12.0, 30.0]);
+        assert!(query.sum_fields(&[]).is_empty());
+    }
+
+    #[derive(Keypath)]
+    struct Job {
+        id: u32,
+        elapsed: Duration,
+    }
+
+    #[test]
+    fn test_sum_and_avg_duration_std() {
+        let jobs = vec![
+            Job { id: 1, elapsed: Duration::from_millis(1_500) },
+            Job { id: 2, elapsed: Duration::from_micros(250) },
+            Job { id: 3, elapsed: Duration::new(2, 1) },
+        ];
+        let query = Query::new(&jobs);
+
+        assert_eq!(query.sum_duration_std(Job::elapsed()), Duration::new(3, 500_250_001));
+        assert_eq!(
+            query.avg_duration_std(Job::elapsed()),
+            Some(Duration::new(1, 166_750_000))
+        );
+
+        let slow = Query::new(&jobs).where_(Job::elapsed(), |d| *d >= Duration::from_secs(1));
+        assert_eq!(slow.avg_duration_std(Job::elapsed()), Some(Duration::new(1, 750_000_000)));
+
+        let none = Query::new(&jobs).where_(Job::id(), |&id| id > 10);
+        assert_eq!(none.sum_duration_std(Job::elapsed()), Duration::ZERO);
+        assert_eq!(none.avg_duration_std(Job::elapsed()), None);
+    }
+
+    #[derive(Clone, Keypath)]
+    struct Reading {
+        sensor: u32,
+        value: f64,
+    }
+
+    #[test]
+    fn test_where_within_stddev_drops_outliers() {
+        let mut readings: Vec<Reading> = (0..10)
+            .map(|i| Reading { sensor: i, value: 10.0 + (i % 3) as f64 * 0.5 })
+            .collect();
+        readings.push(Reading { sensor: 10, value: 100.0 });
+        readings.push(Reading { sensor: 11, value: f64::NAN });
+
+        let kept = Query::new(&readings).where_within_stddev(Reading::value(), 2.0);
+        let sensors: Vec<u32> = kept.iter().map(|r| r.sensor).collect();
+        assert_eq!(sensors, (0..10).collect::<Vec<_>>());
+
+        // A generous bound keeps every finite reading.
+        assert_eq!(Query::new(&readings).where_within_stddev(Reading::value(), 10.0).len(), 11);
+
+        let none = Query::new(&readings).where_(Reading::sensor(), |&s| s > 100);
+        assert!(none.where_within_stddev(Reading::value(), 2.0).is_empty());
+    }
+
+    #[derive(Keypath)]
+    struct PriceVersion {
+        version: u32,
+        valid_from: i64,
+        valid_to: i64,
+    }
+
+    #[test]
+    fn test_where_valid_at_point_in_time() {
+        let versions = vec![
+            PriceVersion { version: 1, valid_from: 0, valid_to: 99 },
+            PriceVersion { version: 2, valid_from: 100, valid_to: 199 },
+            PriceVersion { version: 3, valid_from: 200, valid_to: i64::MAX },
+        ];
+        let valid_at = |at| -> Vec<u32> {
+            Query::new(&versions)
+                .where_valid_at(PriceVersion::valid_from(), PriceVersion::valid_to(), at)
+                .all()
+                .iter()
+                .map(|v| v.version)
+                .collect()
+        };
+
+        assert_eq!(valid_at(50), vec![1]);
+        assert_eq!(valid_at(100), vec![2]);
+        assert_eq!(valid_at(199), vec![2]);
+        assert_eq!(valid_at(i64::MAX), vec![3]);
+        assert!(valid_at(-1).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Clone, serde::Serialize, Keypath)]
+    struct Book {
+        id: u32,
+        title: String,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_page_json_envelope() {
+        let books: Vec<Book> = ["Dune", "Emma", "Beloved", "Ulysses", "Carrie"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| Book { id: i as u32 + 1, title: title.to_string() })
+            .collect();
+
+        let body = Query::new(&books).page_json(Book::title(), false, 1, 2);
+        assert_eq!(body["page"], 1);
+        assert_eq!(body["per_page"], 2);
+        assert_eq!(body["total"], 5);
+        assert_eq!(body["total_pages"], 3);
+        assert_eq!(
+            body["items"],
+            serde_json::json!([
+                { "id": 1, "title": "Dune" },
+                { "id": 2, "title": "Emma" },
+            ])
+        );
+
+        let last = Query::new(&books).page_json(Book::title(), true, 2, 2);
+        assert_eq!(last["items"], serde_json::json!([{ "id": 3, "title": "Beloved" }]));
+    }
+
+    #[cfg(feature = "hll")]
+    #[test]
+    fn test_approx_count_distinct_within_a_few_percent() {
+        let events: Vec<Event> = (0..200_000u32)
+            .map(|i| event(i, "view", (i % 50_000) as i64))
+            .collect();
+        let query = Query::new(&events);
+
+        let exact = query.all().iter().map(|e| e.timestamp).collect::<HashSet<_>>().len() as f64;
+        let estimate = query.approx_count_distinct(Event::timestamp()) as f64;
+        assert!((estimate - exact).abs() / exact < 0.03, "estimate {} vs exact {}", estimate, exact);
+
+        let small = Query::new(&events).where_(Event::id(), |&id| id < 100);
+        assert!(small.approx_count_distinct(Event::timestamp()).abs_diff(100) <= 2);
+
+        let none = Query::new(&events).where_(Event::id(), |&id| id > 1_000_000);
+        assert_eq!(none.approx_count_distinct(Event::timestamp()), 0);
+    }
+
+    #[test]
+    fn test_result_diff_of_two_query_versions() {
+        #[derive(Debug, PartialEq, Eq, Hash, Keypath)]
+        struct Ticket {
+            id: u32,
+            priority: u32,
+        }
+
+        let tickets: Vec<Ticket> = (1..=6).map(|id| Ticket { id, priority: id % 4 }).collect();
+        let old_query = Query::new(&tickets).where_(Ticket::priority(), |&p| p >= 2);
+        let new_query = Query::new(&tickets).where_item(|t| t.priority > 1);
+        let (old, same) = (old_query.all(), new_query.all());
+        let (only_old, only_new) = result_diff(&old, &same);
+        assert!(only_old.is_empty() && only_new.is_empty());
+
+        // Same set in a different order is still no difference.
+        let mut reversed = same.clone();
+        reversed.reverse();
+        assert_eq!(result_diff(&old, &reversed), (vec![], vec![]));
+
+        let changed_query = Query::new(&tickets).where_(Ticket::priority(), |&p| p >= 1);
+        let changed = changed_query.all();
+        let (only_old, only_new) = result_diff(&old, &changed);
+        assert!(only_old.is_empty());
+        assert_eq!(only_new.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 5]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TicketStatus {
+        New,
+        InProgress,
+        Closed,
+    }
+
+    #[derive(Keypath)]
+    struct Ticket {
+        id: u32,
+        status: TicketStatus,
+    }
+
+    fn tickets() -> Vec<Ticket> {
+        use TicketStatus::*;
+        [New, Closed, InProgress, New, Closed]
+            .into_iter()
+            .zip(1..)
+            .map(|(status, id)| Ticket { id, status })
+            .collect()
+    }
+
+    #[test]
+    fn test_where_is_and_where_is_any_on_enum_field() {
+        use crate::lazy::LazyQuery;
+
+        let tickets = tickets();
+        let ids = |query: Query<Ticket>| query.all().iter().map(|t| t.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(Query::new(&tickets).where_is(Ticket::status(), TicketStatus::New)), vec![1, 4]);
+        assert_eq!(
+            ids(Query::new(&tickets).where_is_any(Ticket::status(), &[TicketStatus::New, TicketStatus::InProgress])),
+            vec![1, 3, 4]
+        );
+        assert!(ids(Query::new(&tickets).where_is_any(Ticket::status(), &[])).is_empty());
+
+        let closed: Vec<u32> = LazyQuery::new(&tickets)
+            .where_is(Ticket::status(), TicketStatus::Closed)
+            .map_items(|t| t.id)
+            .collect();
+        assert_eq!(closed, vec![2, 5]);
+        let open = LazyQuery::new(&tickets)
+            .where_is_any(Ticket::status(), &[TicketStatus::New, TicketStatus::InProgress])
+            .count();
+        assert_eq!(open, 3);
+    }
+
+    #[test]
+    fn test_query_over_borrowed_buffer_results_outlive_query() {
+        // Stands in for a read-only buffer such as a memory map, owned elsewhere.
+        struct Buffer {
+            records: Vec<Event>,
+        }
+
+        impl Buffer {
+            fn window(&self, start: usize, len: usize) -> &[Event] {
+                &self.records[start..start + len]
+            }
+        }
+
+        fn clicks(events: &[Event]) -> Vec<&Event> {
+            // The query is dropped on return; the results borrow from `events`.
+            Query::new(events).where_(Event::kind(), |k| k == "click").all()
+        }
+
+        let buffer = Buffer {
+            records: (0..100).map(|i| event(i, if i % 4 == 0 { "click" } else { "view" }, i as i64)).collect(),
+        };
+        let window = buffer.window(10, 20);
+
+        let found = clicks(window);
+        assert_eq!(found.iter().map(|e| e.id).collect::<Vec<_>>(), vec![12, 16, 20, 24, 28]);
+
+        let first = Query::new(window).where_(Event::timestamp(), |&t| t > 25).first();
+        assert_eq!(first.map(|e| e.id), Some(26));
+
+        let query = Query::new(window).where_(Event::kind(), |k| k == "view");
+        assert_eq!(query.count(), 15);
+        assert_eq!(query.max(Event::timestamp()), Some(29));
+        assert_eq!(query.sum(Event::id()), (10..30).filter(|i| i % 4 != 0).sum::<u32>());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_select_records_emit_exactly_requested_keys() {
+        #[derive(Keypath)]
+        struct Member {
+            id: u32,
+            name: String,
+            nickname: Option<String>,
+            password_hash: String,
+        }
+
+        let members = vec![
+            Member { id: 1, name: "Ada".to_string(), nickname: Some("ada".to_string()), password_hash: "x".to_string() },
+            Member { id: 2, name: "Bo".to_string(), nickname: None, password_hash: "y".to_string() },
+        ];
+        let query = Query::new(&members);
+
+        let rows = query.select_records(&[
+            ("id", record_field(Member::id())),
+            ("name", record_field(Member::name())),
+            ("nickname", record_field(Member::nickname())),
+        ]);
+        assert_eq!(rows.len(), 2);
+        for row in &rows {
+            assert_eq!(row.keys().collect::<Vec<_>>(), vec!["id", "name", "nickname"]);
+        }
+        assert_eq!(
+            serde_json::Value::Object(rows[1].clone()),
+            serde_json::json!({ "id": 2, "name": "Bo", "nickname": null })
+        );
+
+        let rows = crate::select_records!(query, "member_id" => Member::id());
+        assert_eq!(rows[0].keys().collect::<Vec<_>>(), vec!["member_id"]);
+        assert!(members.iter().all(|m| !m.password_hash.is_empty()));
+    }
+
+    #[test]
+    fn test_argmax_argmin_return_source_indices() {
+        let sales = sales();
+        let tier = Sale::buyer().then(Buyer::tier());
+
+        // Tiers are Some(2), None, Some(1), Some(3); the None at index 1 is skipped.
+        assert_eq!(Query::new(&sales).argmax(tier.clone()), Some(3));
+        assert_eq!(Query::new(&sales).argmin(tier.clone()), Some(2));
+        assert_eq!(sales[2].id, 3);
+
+        // Filtering keeps indices relative to the source slice.
+        let north = Query::new(&sales).where_(
+            Sale::buyer().then(Buyer::region()).then(Region::name()),
+            |r| r == "north",
+        );
+        assert_eq!(north.argmax(Sale::total()), Some(0));
+        assert_eq!(north.argmin(Sale::total()), Some(2));
+
+        // Ties keep the first occurrence.
+        let events = vec![event(1, "a", 5), event(2, "b", 9), event(3, "c", 9), event(4, "d", 5)];
+        assert_eq!(Query::new(&events).argmax(Event::timestamp()), Some(1));
+        assert_eq!(Query::new(&events).argmin(Event::timestamp()), Some(0));
+
+        let none = Query::new(&sales).where_(Sale::id(), |&id| id > 10);
+        assert_eq!(none.argmax(Sale::total()), None);
+        assert_eq!(Query::new(&sales[1..2]).argmin(tier), None);
+    }
+
+    #[test]
+    #[cfg(feature = "datetime")]
+    fn test_group_by_hour_of_day_ignores_date() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        #[derive(Keypath)]
+        struct Visit {
+            path: String,
+            at: DateTime<Utc>,
+        }
+        let visit = |path: &str, day, hour, minute| Visit {
+            path: path.to_string(),
+            at: Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap(),
+        };
+        let visits = vec![
+            visit("/", 1, 9, 5),
+            visit("/", 2, 9, 59),
+            visit("/about", 17, 9, 30),
+            visit("/", 3, 23, 0),
+            visit("/", 4, 0, 15),
+            visit("/admin", 4, 9, 0),
+        ];
+
+        let by_hour = Query::new(&visits)
+            .where_(Visit::path(), |p| !p.starts_with("/admin"))
+            .group_by_hour_of_day(Visit::at());
+        assert_eq!(by_hour[9], 3);
+        assert_eq!(by_hour[23], 1);
+        assert_eq!(by_hour[0], 1);
+        assert_eq!(by_hour.iter().sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_running_max_and_min_follow_order() {
+        let reading = |sensor, value| Reading { sensor, value };
+        let readings = vec![reading(2, 3.0), reading(1, 5.0), reading(3, 7.0), reading(2, 1.0), reading(4, 4.0)];
+        let series = |pairs: Vec<(Reading, f64)>| -> Vec<(u32, f64, f64)> {
+            pairs.into_iter().map(|(r, v)| (r.sensor, r.value, v)).collect()
+        };
+
+        // Ordered by sensor, ties in source order: 1 5, 2 3, 2 1, 3 7, 4 4.
+        let highs = series(Query::new(&readings).running_max(Reading::sensor(), Reading::value()));
+        assert_eq!(highs, vec![(1, 5.0, 5.0), (2, 3.0, 5.0), (2, 1.0, 5.0), (3, 7.0, 7.0), (4, 4.0, 7.0)]);
+
+        let lows = series(Query::new(&readings).running_min(Reading::sensor(), Reading::value()));
+        assert_eq!(lows, vec![(1, 5.0, 5.0), (2, 3.0, 3.0), (2, 1.0, 1.0), (3, 7.0, 1.0), (4, 4.0, 1.0)]);
+
+        let none = Query::new(&readings).where_(Reading::value(), |&v| v > 10.0);
+        assert!(none.running_max(Reading::sensor(), Reading::value()).is_empty());
+    }
+
+    #[test]
+    fn test_first_per_key_keeps_first_occurrence() {
+        let events = vec![
+            event(1, "click", 10),
+            event(2, "view", 20),
+            event(3, "click", 30),
+            event(4, "scroll", 40),
+            event(5, "view", 50),
+            event(6, "scroll", 5),
+        ];
+
+        let firsts: Vec<u32> = Query::new(&events).first_per_key(Event::kind()).iter().map(|e| e.id).collect();
+        assert_eq!(firsts, vec![1, 2, 4]);
+
+        // Filters apply before deduplication.
+        let later = Query::new(&events).where_(Event::timestamp(), |&t| t > 15);
+        let ids: Vec<u32> = later.first_per_key(Event::kind()).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+
+        // Items with a missing key are skipped.
+        let sales = sales();
+        let tiers: Vec<u32> = Query::new(&sales)
+            .first_per_key(Sale::buyer().then(Buyer::tier()))
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(tiers, vec![1, 3, 4]);
+    }
+
+    #[derive(Keypath)]
+    struct Contact {
+        id: u32,
+        mobile: Option<String>,
+        home: Option<String>,
+        work: Option<String>,
+    }
+
+    #[test]
+    fn test_select_coalesce_picks_first_present_field() {
+        let phone = |n: &str| Some(n.to_string());
+        let contacts = vec![
+            Contact { id: 1, mobile: phone("m-1"), home: phone("h-1"), work: None },
+            Contact { id: 2, mobile: None, home: phone("h-2"), work: phone("w-2") },
+            Contact { id: 3, mobile: None, home: None, work: phone("w-3") },
+            Contact { id: 4, mobile: None, home: None, work: None },
+        ];
+
+        let phones = Query::new(&contacts)
+            .select_coalesce(vec![Contact::mobile(), Contact::home(), Contact::work()]);
+        assert_eq!(phones, vec![phone("m-1"), phone("h-2"), phone("w-3"), None]);
+
+        let work_first = Query::new(&contacts)
+            .where_(Contact::id(), |&id| id <= 2)
+            .select_coalesce(vec![Contact::work(), Contact::mobile()]);
+        assert_eq!(work_first, vec![phone("m-1"), phone("w-2")]);
+    }
+
+    #[test]
+    fn test_partition_explained_reports_first_failed_label() {
+        let events = vec![
+            event(1, "click", 2000),
+            event(2, "view", 2000),
+            event(3, "click", 500),
+            event(4, "click", 3000),
+        ];
+
+        let (passed, rejected) = Query::new(&events)
+            .where_labeled("not a click", Event::kind(), |k| k == "click")
+            .where_labeled("too early", Event::timestamp(), |&t| t >= 1000)
+            .where_labeled("id too high", Event::id(), |&id| id < 3)
+            .partition_explained();
+
+        assert_eq!(passed.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1]);
+        let reasons: Vec<(u32, &str)> = rejected.iter().map(|(e, why)| (e.id, *why)).collect();
+        // Event 3 fails both the second and third filters; the second is reported.
+        assert_eq!(reasons, vec![(2, "not a click"), (3, "too early"), (4, "id too high")]);
+
+        let (_, rejected) = Query::new(&events)
+            .where_(Event::kind(), |k| k == "click")
+            .partition_explained();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].1, "unlabeled");
+    }
+
+    #[derive(Clone)]
+    struct Post {
+        id: u32,
+        tags: Vec<String>,
+    }
+
+    impl Post {
+        fn tags() -> KeyPaths<Post, Vec<String>> {
+            KeyPaths::readable(|p: &Post| &p.tags)
+        }
+    }
+
+    fn posts() -> Vec<Post> {
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect();
+        vec![
+            Post { id: 1, tags: tags(&["rust", "sql"]) },
+            Post { id: 2, tags: tags(&["rust"]) },
+            Post { id: 3, tags: tags(&["go", "sql", "rust"]) },
+            Post { id: 4, tags: tags(&[]) },
+        ]
+    }
+
+    fn ids(query: &Query<Post>) -> Vec<u32> {
+        query.all().iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn test_where_vec_contains_any() {
+        let posts = posts();
+        let wanted = ["sql".to_string(), "python".to_string()];
+
+        let partial = Query::new(&posts).where_vec_contains_any(Post::tags(), &wanted);
+        assert_eq!(ids(&partial), vec![1, 3]);
+
+        let none = Query::new(&posts).where_vec_contains_any(Post::tags(), &["python".to_string()]);
+        assert!(ids(&none).is_empty());
+
+        let empty = Query::new(&posts).where_vec_contains_any(Post::tags(), &[]);
+        assert!(ids(&empty).is_empty());
+    }
+
+    #[test]
+    fn test_where_vec_contains_all() {
+        let posts = posts();
+        let wanted = ["rust".to_string(), "sql".to_string()];
+
+        let full = Query::new(&posts).where_vec_contains_all(Post::tags(), &wanted);
+        assert_eq!(ids(&full), vec![1, 3]);
+
+        let partial = Query::new(&posts)
+            .where_vec_contains_all(Post::tags(), &["rust".to_string(), "go".to_string()]);
+        assert_eq!(ids(&partial), vec![3]);
+
+        let empty = Query::new(&posts).where_vec_contains_all(Post::tags(), &[]);
+        assert_eq!(ids(&empty), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "window_ms must be positive")]
+    fn test_count_per_window_rejects_zero_window() {
+        let events = vec![event(1, "click", 0)];
+        Query::new(&events).count_per_window(Event::timestamp(), 0);
+    }
+}
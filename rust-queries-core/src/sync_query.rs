@@ -0,0 +1,157 @@
+//! Queries that can be moved to other threads.
+//!
+//! This module provides the `SyncQuery` struct, which holds only `SyncFilter`s
+//! so that a query assembled on one thread can be sent to a worker thread or
+//! rayon task and executed there.
+
+use crate::query::{Query, SyncFilter};
+
+/// A query built from thread-safe filters.
+///
+/// `Query` stores its filters as plain boxed closures and is therefore not
+/// `Send`. `SyncQuery` only accepts `SyncFilter`s, so it is `Send` and `Sync`
+/// whenever `T: Sync`, and the whole query can be built once and moved into
+/// another thread. Use `into_query` on the worker to get the full `Query` API.
+///
+/// # Example
+///
+/// ```ignore
+/// let products: &'static [Product] = load_catalog();
+/// let query = SyncQuery::new(products)
+///     .where_sync(&SyncFilter::new(|p: &Product| p.price < 100.0))
+///     .where_sync(&SyncFilter::new(|p: &Product| p.stock > 0));
+///
+/// let handle = std::thread::spawn(move || query.count());
+/// ```
+pub struct SyncQuery<'a, T: 'static> {
+    data: &'a [T],
+    filters: Vec<SyncFilter<T>>,
+}
+
+impl<'a, T: 'static> SyncQuery<'a, T> {
+    /// Creates a new thread-safe query from a slice of data.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = SyncQuery::new(&products);
+    /// ```
+    pub fn new(data: &'a [T]) -> Self {
+        Self {
+            data,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Adds a thread-safe filter.
+    ///
+    /// The filter is shared, not copied, like `Query::where_sync`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cheap = SyncFilter::new(|p: &Product| p.price < 100.0);
+    /// let query = SyncQuery::new(&products).where_sync(&cheap);
+    /// ```
+    pub fn where_sync(mut self, filter: &SyncFilter<T>) -> Self {
+        self.filters.push(filter.clone());
+        self
+    }
+
+    fn matching(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.data
+            .iter()
+            .filter(|item| self.filters.iter().all(|f| f.matches(item)))
+    }
+
+    /// Returns all items matching the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results = query.all();
+    /// ```
+    pub fn all(&self) -> Vec<&'a T> {
+        self.matching().collect()
+    }
+
+    /// Returns the first item matching the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first = query.first();
+    /// ```
+    pub fn first(&self) -> Option<&'a T> {
+        self.matching().next()
+    }
+
+    /// Counts the items matching the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let count = query.count();
+    /// ```
+    pub fn count(&self) -> usize {
+        self.matching().count()
+    }
+
+    /// Checks if any item matches the filters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let has_results = query.exists();
+    /// ```
+    pub fn exists(&self) -> bool {
+        self.matching().next().is_some()
+    }
+
+    /// Converts into a `Query` with the same filters, for the rest of the query API.
+    ///
+    /// The resulting `Query` is not `Send`, so convert once the query has
+    /// reached the thread that runs it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let handle = std::thread::spawn(move || query.into_query().sum(Product::price()));
+    /// ```
+    pub fn into_query(self) -> Query<'a, T> {
+        self.filters
+            .iter()
+            .fold(Query::new(self.data), |query, filter| query.where_sync(filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::tests::{event, Event};
+
+    #[test]
+    fn test_built_query_moves_into_spawned_thread() {
+        let events = vec![event(1, "click", 10), event(2, "view", 20), event(3, "click", 30), event(4, "login", 40)];
+        let filter = SyncFilter::new(|e: &Event| e.kind == "click").or(SyncFilter::new(|e: &Event| e.kind == "login"));
+        let query = SyncQuery::new(&events)
+            .where_sync(&filter)
+            .where_sync(&SyncFilter::new(|e: &Event| e.timestamp > 10));
+
+        let result = std::thread::scope(|scope| {
+            scope
+                .spawn(move || {
+                    let ids: Vec<u32> = query.all().iter().map(|e| e.id).collect();
+                    (ids, query.count(), query.into_query().sum(Event::timestamp()))
+                })
+                .join()
+                .unwrap()
+        });
+        assert_eq!(result, (vec![3, 4], 2, 70));
+
+        let query = SyncQuery::new(&events).where_sync(&filter);
+        assert_eq!(query.first().map(|e| e.id), Some(1));
+        assert!(query.exists());
+        assert!(!SyncQuery::new(&events[1..2]).where_sync(&filter).exists());
+    }
+}
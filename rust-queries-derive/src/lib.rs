@@ -1,8 +1,8 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Data, Fields};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, Data, Fields, LitStr};
 
 /// Derive macro to generate Queryable implementations
 /// 
@@ -36,7 +36,17 @@ pub fn derive_queryable(input: TokenStream) -> TokenStream {
 
 /// Derive macro to generate helper methods for query building
 /// 
-/// This macro generates convenience methods for common query patterns
+/// This macro generates convenience methods for common query patterns,
+/// plus a `COLUMNS` constant and `columns()` method listing the reported
+/// field names, and a `get_column(&self, name)` method that renders a reported
+/// field looked up by name at runtime. Values are rendered with `Display`
+/// when the field type implements it and with `Debug` otherwise, so every
+/// reported field must implement one of the two.
+/// 
+/// Fields can be customized with the `#[query(...)]` attribute:
+/// 
+/// - `#[query(skip)]` omits the field from the docs and `COLUMNS`
+/// - `#[query(rename = "display_name")]` reports the field under another name
 /// 
 /// # Example
 /// 
@@ -44,27 +54,29 @@ pub fn derive_queryable(input: TokenStream) -> TokenStream {
 /// #[derive(QueryBuilder)]
 /// struct Product {
 ///     id: u32,
+///     #[query(rename = "product_name")]
 ///     name: String,
 ///     price: f64,
+///     #[query(skip)]
+///     internal_code: String,
 /// }
+/// 
+/// assert_eq!(Product::COLUMNS, &["id", "product_name", "price"]);
+/// assert_eq!(product.get_column("price"), Some("9.5".to_string()));
+/// assert_eq!(product.get_column("internal_code"), None);
 /// ```
-#[proc_macro_derive(QueryBuilder)]
+#[proc_macro_derive(QueryBuilder, attributes(query))]
 pub fn derive_query_builder(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
     
-    // Extract field names for documentation
-    let fields = match &input.data {
-        Data::Struct(data) => {
-            match &data.fields {
-                Fields::Named(fields) => {
-                    fields.named.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>()
-                }
-                _ => vec![],
-            }
-        }
-        _ => vec![],
+    // Extract reported field names and idents for documentation, COLUMNS and get_column
+    let reported = match reported_columns(&input.data) {
+        Ok(columns) => columns.unwrap_or_default(),
+        Err(err) => return TokenStream::from(err.to_compile_error()),
     };
+    let fields: Vec<&String> = reported.iter().map(|(column, _)| column).collect();
+    let idents = reported.iter().map(|(_, ident)| ident);
 
     let field_docs = if !fields.is_empty() {
         let field_list = fields.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n///   ");
@@ -75,6 +87,24 @@ pub fn derive_query_builder(input: TokenStream) -> TokenStream {
 
     let expanded = quote! {
         impl #name {
+            /// Reported column names, in declaration order.
+            pub const COLUMNS: &'static [&'static str] = &[#(#fields),*];
+
+            /// Returns the reported column names, in declaration order.
+            pub fn columns() -> &'static [&'static str] {
+                Self::COLUMNS
+            }
+
+            /// Renders the value of the reported column `name`, or `None` if there is no such column.
+            pub fn get_column(&self, name: &str) -> Option<String> {
+                #[allow(unused_imports)]
+                use rust_queries_core::column::{ViaDebug as _, ViaDisplay as _};
+                match name {
+                    #(#fields => Some((&rust_queries_core::column::Column(&self.#idents)).render_column()),)*
+                    _ => None,
+                }
+            }
+
             #[doc = #field_docs]
             /// Creates a new eager Query from a slice of items
             pub fn query(items: &[Self]) -> rust_queries_core::Query<Self> {
@@ -91,3 +121,165 @@ pub fn derive_query_builder(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Derive macro to generate a column enum for choosing a sort order at runtime
+///
+/// For a struct `Product` this generates `ProductColumn`, with one variant per
+/// reported field (field names in UpperCamelCase). The enum parses from the
+/// reported column names with `FromStr`, so a sort column taken from user input
+/// can be validated once and then only ever select one of the struct's fields.
+/// `key_path_ord()` returns a comparator for the chosen field. Fields are
+/// compared with `Ord` when the field type implements it and with `PartialOrd`
+/// otherwise (incomparable values such as NaN compare equal), so every
+/// reported field must implement one of the two.
+///
+/// Fields honor the same `#[query(skip)]` and `#[query(rename = "...")]`
+/// attributes as `QueryBuilder`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(OrderColumn)]
+/// struct Product {
+///     id: u32,
+///     name: String,
+///     price: f64,
+/// }
+///
+/// let column: ProductColumn = params.sort.parse()?; // "price" -> ProductColumn::Price
+/// let cmp = column.key_path_ord();
+/// let sorted = LazyQuery::new(&products).sort_by(|a, b| cmp(a, b));
+/// ```
+#[proc_macro_derive(OrderColumn, attributes(query))]
+pub fn derive_order_column(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let vis = &input.vis;
+    let enum_name = format_ident!("{}Column", name);
+
+    let reported = match reported_columns(&input.data) {
+        Ok(Some(columns)) => columns,
+        Ok(None) => {
+            return TokenStream::from(
+                syn::Error::new_spanned(name, "OrderColumn can only be derived for structs with named fields")
+                    .to_compile_error(),
+            )
+        }
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let columns: Vec<&String> = reported.iter().map(|(column, _)| column).collect();
+    let idents: Vec<&syn::Ident> = reported.iter().map(|(_, ident)| ident).collect();
+    let variants: Vec<syn::Ident> = idents
+        .iter()
+        .map(|ident| format_ident!("{}", upper_camel_case(&ident.to_string())))
+        .collect();
+    let variant_docs = columns.iter().map(|column| format!("The `{}` column.", column));
+    let enum_doc = format!("A sortable column of [`{}`], generated by `#[derive(OrderColumn)]`.", name);
+
+    let expanded = quote! {
+        #[doc = #enum_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #enum_name {
+            #(#[doc = #variant_docs] #variants,)*
+        }
+
+        impl #enum_name {
+            /// Every column, in declaration order.
+            pub const ALL: &'static [#enum_name] = &[#(#enum_name::#variants),*];
+
+            /// Returns the reported column name.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    #(#enum_name::#variants => #columns,)*
+                }
+            }
+
+            /// Returns a comparator that orders items by this column, ascending.
+            pub fn key_path_ord(self) -> fn(&#name, &#name) -> ::std::cmp::Ordering {
+                #[allow(unused_imports)]
+                use rust_queries_core::column::{ViaOrd as _, ViaPartialOrd as _};
+                match self {
+                    #(#enum_name::#variants => |a, b| {
+                        (&rust_queries_core::column::Column(&a.#idents)).compare_column(&b.#idents)
+                    },)*
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for #enum_name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    #(#columns => Ok(#enum_name::#variants),)*
+                    _ => Err(format!("unknown column `{}`", s)),
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Collects `(reported name, field ident)` pairs for non-skipped fields.
+///
+/// Returns `Ok(None)` for anything other than a struct with named fields.
+fn reported_columns(data: &Data) -> syn::Result<Option<Vec<(String, syn::Ident)>>> {
+    let Data::Struct(data) = data else {
+        return Ok(None);
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Ok(None);
+    };
+
+    let mut columns = Vec::new();
+    for field in &fields.named {
+        let FieldOptions { skip, rename } = parse_query_attrs(field)?;
+        if !skip {
+            let ident = field.ident.clone().unwrap();
+            columns.push((rename.unwrap_or_else(|| ident.to_string()), ident));
+        }
+    }
+    Ok(Some(columns))
+}
+
+/// Converts a snake_case field name to UpperCamelCase, dropping any `r#` prefix.
+fn upper_camel_case(field: &str) -> String {
+    field
+        .trim_start_matches("r#")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+/// Options parsed from `#[query(...)]` field attributes.
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    rename: Option<String>,
+}
+
+fn parse_query_attrs(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("query")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("rename") {
+                let value: LitStr = meta.value()?.parse()?;
+                options.rename = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported query attribute, expected `skip` or `rename = \"...\"`"))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
@@ -0,0 +1,9 @@
+#[test]
+fn query_builder_attributes() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/skip_field.rs");
+    t.pass("tests/ui/rename_field.rs");
+    t.pass("tests/ui/get_column.rs");
+    t.pass("tests/ui/order_column.rs");
+    t.compile_fail("tests/ui/unknown_attribute.rs");
+}
@@ -0,0 +1,41 @@
+use rust_queries_derive::QueryBuilder;
+
+#[derive(Debug)]
+enum Status {
+    Active,
+}
+
+#[derive(QueryBuilder)]
+struct Product {
+    id: u32,
+    #[query(rename = "title")]
+    name: String,
+    price: f64,
+    status: Status,
+    discount: Option<u32>,
+    #[query(skip)]
+    internal_code: String,
+}
+
+fn main() {
+    let product = Product {
+        id: 7,
+        name: "Laptop".to_string(),
+        price: 999.5,
+        status: Status::Active,
+        discount: Some(10),
+        internal_code: "X-1".to_string(),
+    };
+
+    assert_eq!(product.get_column("id").as_deref(), Some("7"));
+    assert_eq!(product.get_column("title").as_deref(), Some("Laptop"));
+    assert_eq!(product.get_column("price").as_deref(), Some("999.5"));
+    // Types without `Display` fall back to `Debug`.
+    assert_eq!(product.get_column("status").as_deref(), Some("Active"));
+    assert_eq!(product.get_column("discount").as_deref(), Some("Some(10)"));
+
+    assert_eq!(product.get_column("name"), None);
+    assert_eq!(product.get_column("internal_code"), None);
+    assert_eq!(product.get_column("unknown"), None);
+    assert!(!product.internal_code.is_empty());
+}
@@ -0,0 +1,43 @@
+use rust_queries_core::LazyQuery;
+use rust_queries_derive::OrderColumn;
+
+#[derive(OrderColumn)]
+struct Product {
+    id: u32,
+    #[query(rename = "title")]
+    name: String,
+    unit_price: f64,
+    #[query(skip)]
+    internal_code: String,
+}
+
+fn main() {
+    let product = |id, name: &str, unit_price| Product {
+        id,
+        name: name.to_string(),
+        unit_price,
+        internal_code: "X".to_string(),
+    };
+    let products = vec![
+        product(1, "Mouse", 20.0),
+        product(2, "Laptop", 999.0),
+        product(3, "Cable", 5.0),
+    ];
+
+    let column: ProductColumn = "unit_price".parse().unwrap();
+    assert_eq!(column, ProductColumn::UnitPrice);
+    assert_eq!(column.as_str(), "unit_price");
+
+    let cmp = column.key_path_ord();
+    let ids: Vec<u32> = LazyQuery::new(&products).sort_by(|a, b| cmp(a, b)).iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![3, 1, 2]);
+
+    let cmp = "title".parse::<ProductColumn>().unwrap().key_path_ord();
+    let ids: Vec<u32> = LazyQuery::new(&products).sort_by(|a, b| cmp(a, b)).iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+
+    assert_eq!(ProductColumn::ALL, &[ProductColumn::Id, ProductColumn::Name, ProductColumn::UnitPrice]);
+    assert!("name".parse::<ProductColumn>().is_err());
+    assert!("internal_code".parse::<ProductColumn>().is_err());
+    assert!(products.iter().all(|p| !p.internal_code.is_empty()));
+}
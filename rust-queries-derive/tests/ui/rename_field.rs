@@ -0,0 +1,27 @@
+use rust_queries_derive::QueryBuilder;
+
+#[derive(QueryBuilder)]
+struct Product {
+    id: u32,
+    #[query(rename = "display_name")]
+    name: String,
+    #[query(rename = "unit_price")]
+    price: f64,
+    #[query(skip, rename = "hidden")]
+    internal_code: String,
+}
+
+fn main() {
+    assert_eq!(Product::COLUMNS, &["id", "display_name", "unit_price"]);
+    assert_eq!(Product::columns(), &["id", "display_name", "unit_price"]);
+
+    let products = vec![Product {
+        id: 1,
+        name: "Laptop".to_string(),
+        price: 999.99,
+        internal_code: "X-1".to_string(),
+    }];
+    assert_eq!(Product::lazy_query(&products).count(), 1);
+    assert_eq!((products[0].id, products[0].name.as_str()), (1, "Laptop"));
+    assert!(products[0].price > 0.0 && !products[0].internal_code.is_empty());
+}
@@ -0,0 +1,24 @@
+use rust_queries_derive::QueryBuilder;
+
+#[derive(QueryBuilder)]
+struct Product {
+    id: u32,
+    name: String,
+    #[query(skip)]
+    internal_code: String,
+    price: f64,
+}
+
+fn main() {
+    assert_eq!(Product::COLUMNS, &["id", "name", "price"]);
+    assert_eq!(Product::columns(), Product::COLUMNS);
+
+    let products = vec![Product {
+        id: 1,
+        name: "Laptop".to_string(),
+        internal_code: "X-1".to_string(),
+        price: 999.99,
+    }];
+    assert_eq!(Product::query(&products).count(), 1);
+    assert_eq!(products[0].internal_code, "X-1");
+}
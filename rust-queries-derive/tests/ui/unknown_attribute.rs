@@ -0,0 +1,10 @@
+use rust_queries_derive::QueryBuilder;
+
+#[derive(QueryBuilder)]
+struct Product {
+    id: u32,
+    #[query(hidden)]
+    name: String,
+}
+
+fn main() {}
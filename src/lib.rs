@@ -48,7 +48,7 @@
 pub use rust_queries_core::*;
 
 // Re-export derive macros
-pub use rust_queries_derive::{Queryable as QueryableDerive, QueryBuilder};
+pub use rust_queries_derive::{Queryable as QueryableDerive, QueryBuilder, OrderColumn};
 
 // Re-export keypath derive macro for convenience
 pub use key_paths_derive::Keypath;